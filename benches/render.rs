@@ -0,0 +1,28 @@
+extern crate sdl2;
+extern crate sdl2_ttf;
+extern crate test;
+
+use sdl2::pixels::Color;
+use sdl2_ttf::Font;
+use test::Bencher;
+
+static FONT_PATH: &'static str = "tests/fonts/DejaVuSansMono.ttf";
+
+// Compare rendering a glyph with and without the init check the safe
+// render methods make, for the glyph heavy case *_unchecked exists for.
+
+#[bench]
+fn bench_render_char_blended(b: &mut Bencher) {
+    assert!(sdl2_ttf::init());
+    let font = Font::from_file(FONT_PATH, 16).unwrap();
+    let fg = Color::RGB(255, 255, 255);
+    b.iter(|| font.render_char_blended('g', &fg).unwrap());
+}
+
+#[bench]
+fn bench_render_char_blended_unchecked(b: &mut Bencher) {
+    assert!(sdl2_ttf::init());
+    let font = Font::from_file(FONT_PATH, 16).unwrap();
+    let fg = Color::RGB(255, 255, 255);
+    b.iter(|| unsafe { font.render_char_blended_unchecked('g', &fg).unwrap() });
+}
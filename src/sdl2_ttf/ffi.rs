@@ -2,7 +2,7 @@ extern crate sdl2;
 
 use libc::{c_int, c_char, c_long, c_void, uint16_t, uint32_t};
 use sdl2::surface::ll::SDL_Surface;
-use sdl2::pixels::ll::SDL_Color;
+use sdl2::pixels::ll::{SDL_Color, SDL_Palette};
 use sdl2::rwops::ll::SDL_RWops;
 use sdl2::version::ll::SDL_version;
 
@@ -17,6 +17,7 @@ pub const TTF_HINTING_NORMAL    : c_int = 0;
 pub const TTF_HINTING_LIGHT     : c_int = 1;
 pub const TTF_HINTING_MONO      : c_int = 2;
 pub const TTF_HINTING_NONE      : c_int = 3;
+pub const TTF_HINTING_LIGHT_SUBPIXEL : c_int = 4;
 
 pub type TTF_Font = c_void;
 extern "C" {
@@ -100,3 +101,17 @@ extern "C" {
     pub fn TTF_GetFontKerningSize(font: *const TTF_Font, prev_index: c_int,
                                   index: c_int) -> c_int;
 }
+
+// From SDL2 itself, for converting rendered surfaces to a known format,
+// recoloring them and opening in-memory fonts.
+pub const SDL_PIXELFORMAT_ARGB8888 : uint32_t = 0x16362004;
+pub const SDL_PIXELFORMAT_INDEX8   : uint32_t = 0x13000801;
+
+extern "C" {
+    pub fn SDL_RWFromConstMem(mem: *const c_void, size: c_int) -> *const SDL_RWops;
+    pub fn SDL_ConvertSurfaceFormat(src: *const SDL_Surface, pixel_format: uint32_t,
+                                    flags: uint32_t) -> *const SDL_Surface;
+    pub fn SDL_SetPaletteColors(palette: *const SDL_Palette, colors: *const SDL_Color,
+                                firstcolor: c_int, ncolors: c_int) -> c_int;
+    pub fn SDL_GetColorKey(surface: *const SDL_Surface, key: *mut uint32_t) -> c_int;
+}
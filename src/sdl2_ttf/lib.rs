@@ -12,14 +12,34 @@ A binding for SDL2_ttf.
 extern crate libc;
 extern crate sdl2;
 
-use libc::{c_int, c_long};
+use libc::{c_char, c_int, c_long, c_void};
 use std::c_str::CString;
-use std::num::FromPrimitive;
+use std::char;
+use std::cmp;
+use std::collections::HashMap;
+use std::default::Default;
+use std::fmt;
+use std::mem;
+use std::io::{BufWriter, Seek};
+use std::iter::range_inclusive;
+use std::kinds::marker::ContravariantLifetime;
+use std::num;
+use std::num::{Bounded, FromPrimitive, NumCast};
+use std::path::BytesContainer;
+use std::str;
+use std::str::FromStr;
+use std::sync::{Arc, Once, ONCE_INIT};
+use std::sync::atomic::{AtomicBool, AtomicUint, INIT_ATOMIC_BOOL, INIT_ATOMIC_UINT, SeqCst};
+use sdl2::surface;
 use sdl2::surface::Surface;
+use sdl2::surface::ll::SDL_Surface;
 use sdl2::get_error;
+use sdl2::hint;
 use sdl2::pixels;
 use sdl2::pixels::Color;
 use sdl2::pixels::ll::SDL_Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Renderer, Texture, TextureAccess};
 use sdl2::rwops::RWops;
 use sdl2::version::Version;
 use sdl2::SdlResult;
@@ -45,6 +65,9 @@ mod others {
 #[allow(non_camel_case_types, dead_code)]
 mod ffi;
 
+/// The opaque SDL2_ttf font handed out by Font::raw().
+pub use ffi::TTF_Font;
+
 #[inline]
 fn color_to_c_color(color: Color) -> SDL_Color {
     match color {
@@ -53,8 +76,310 @@ fn color_to_c_color(color: Color) -> SDL_Color {
     }
 }
 
-/// Font Style
-bitflags!(flags FontStyle : c_int {
+/// Types usable as a text color: `Color` itself, or `(r, g, b)` and
+/// `(r, g, b, a)` tuples.
+pub trait ToColor {
+    fn to_color(&self) -> Color;
+}
+
+impl ToColor for Color {
+    fn to_color(&self) -> Color {
+        self.clone()
+    }
+}
+
+impl<'a, T: ToColor> ToColor for &'a T {
+    fn to_color(&self) -> Color {
+        (**self).to_color()
+    }
+}
+
+impl ToColor for (u8, u8, u8) {
+    fn to_color(&self) -> Color {
+        let (r, g, b) = *self;
+        pixels::Color::RGB(r, g, b)
+    }
+}
+
+impl ToColor for (u8, u8, u8, u8) {
+    fn to_color(&self) -> Color {
+        let (r, g, b, a) = *self;
+        pixels::Color::RGBA(r, g, b, a)
+    }
+}
+
+/// Converts a char to the 16-bit code SDL2_ttf's glyph functions take, or
+/// None if it lies outside the Basic Multilingual Plane and would otherwise
+/// be truncated to an unrelated glyph.
+#[inline]
+fn char_to_u16(ch: char) -> Option<u16> {
+    if ch as u32 <= 0xFFFF {
+        Some(ch as u16)
+    } else {
+        None
+    }
+}
+
+/// char_to_u16 for the rendering functions, which report such characters
+/// as errors.
+fn glyph_code(ch: char) -> SdlResult<u16> {
+    match char_to_u16(ch) {
+        Some(code) => Ok(code),
+        None => Err(format!("U+{:X} is outside the Basic Multilingual Plane, \
+                             which SDL2_ttf cannot render as a glyph", ch as u32))
+    }
+}
+
+/// Copies a face name borrowed from SDL2_ttf. Names of legacy fonts may be
+/// in Latin-1 or MacRoman rather than UTF8, so invalid bytes are replaced
+/// instead of failing.
+unsafe fn name_from_ll(cname: *const c_char) -> Option<String> {
+    if cname.is_null() {
+        None
+    } else {
+        // not owns buffer
+        let cname = CString::new(cname, false);
+        Some(String::from_utf8_lossy(cname.as_bytes_no_nul()).to_string())
+    }
+}
+
+/// Whether ch is in one of the blocks of combining diacritical marks, which
+/// belong with the char before them.
+fn is_combining_mark(ch: char) -> bool {
+    match ch as u32 {
+        0x0300...0x036F | 0x1AB0...0x1AFF | 0x1DC0...0x1DFF |
+        0x20D0...0x20FF | 0xFE20...0xFE2F => true,
+        _ => false
+    }
+}
+
+/// Splits text before every char that is not a combining mark, so that each
+/// piece is a base char followed by its marks. Other multi-char graphemes,
+/// e.g. flags, are still split char by char.
+fn split_clusters(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::with_capacity(text.len());
+    let mut start = 0;
+    for (i, ch) in text.char_indices() {
+        if i > 0 && !is_combining_mark(ch) {
+            clusters.push(text.slice(start, i));
+            start = i;
+        }
+    }
+    if start < text.len() {
+        clusters.push(text.slice_from(start));
+    }
+    clusters
+}
+
+/// The resolution SDL2_ttf renders at, making one point one pixel.
+const DEFAULT_DPI: u32 = 72;
+
+/// The point size that renders ptsize points at vdpi as pixels at 72 DPI.
+fn scale_ptsize(ptsize: int, vdpi: u32) -> int {
+    (ptsize * vdpi as int + DEFAULT_DPI as int / 2) / DEFAULT_DPI as int
+}
+
+/// Size of the stack buffer `render_fmt_blended` formats into.
+const FMT_BUFFER_SIZE: uint = 256;
+
+/// Fails with a clear message when the truetype font API is not initialized,
+/// rather than passing on whatever stale error SDL happens to hold.
+#[inline]
+fn check_inited() -> SdlResult<()> {
+    if was_inited() {
+        Ok(())
+    } else {
+        Err("SDL2_ttf not initialized".to_string())
+    }
+}
+
+/// Creates a fully transparent surface in the ARGB8888 format that blended
+/// rendering produces.
+fn new_argb_surface(width: int, height: int) -> SdlResult<Surface> {
+    Surface::new(surface::SWSURFACE, width, height, 32,
+                 0x00ff0000, 0x0000ff00, 0x000000ff, 0xff000000)
+}
+
+// Byte offsets of the channels of an ARGB8888 pixel in memory.
+#[cfg(target_endian = "little")] const ALPHA_BYTE: uint = 3;
+#[cfg(target_endian = "little")] const RED_BYTE:   uint = 2;
+#[cfg(target_endian = "little")] const GREEN_BYTE: uint = 1;
+#[cfg(target_endian = "little")] const BLUE_BYTE:  uint = 0;
+#[cfg(target_endian = "big")] const ALPHA_BYTE: uint = 0;
+#[cfg(target_endian = "big")] const RED_BYTE:   uint = 1;
+#[cfg(target_endian = "big")] const GREEN_BYTE: uint = 2;
+#[cfg(target_endian = "big")] const BLUE_BYTE:  uint = 3;
+
+/// Converts a surface of any format into a new ARGB8888 one.
+fn to_argb(surface: &Surface) -> SdlResult<Surface> {
+    let raw = unsafe {
+        ffi::SDL_ConvertSurfaceFormat(surface.raw(), ffi::SDL_PIXELFORMAT_ARGB8888, 0)
+    };
+    if raw.is_null() {
+        Err(get_error())
+    } else {
+        Ok(Surface::from_ll(raw, true))
+    }
+}
+
+/// Converts a numeric argument to the C type SDL takes, failing rather than
+/// letting an out of range value wrap around on the way.
+fn to_c<T: NumCast + fmt::Show, U: NumCast>(value: T, what: &str) -> SdlResult<U> {
+    match num::cast(value) {
+        Some(converted) => Ok(converted),
+        None => Err(format!("{} {} is out of range", what, value))
+    }
+}
+
+/// Builds the path of a font file, failing rather than panicking when the
+/// name holds a NUL byte, which no path can.
+fn to_path<P: BytesContainer>(filename: P) -> SdlResult<Path> {
+    let bytes = filename.container_as_bytes();
+    match Path::new_opt(bytes) {
+        Some(path) => Ok(path),
+        None => Err(format!("{}: path contains a NUL byte",
+                            String::from_utf8_lossy(bytes).escape_default()))
+    }
+}
+
+/// Blits the whole of `src` onto `dst`, with its top-left corner at (x, y).
+fn blit_at(src: &Surface, dst: &mut Surface, x: int, y: int) -> SdlResult<()> {
+    let dst_rect = Rect::new(x as i32, y as i32,
+                             src.get_width() as i32, src.get_height() as i32);
+    if src.blit(None, dst, Some(dst_rect)) {
+        Ok(())
+    } else {
+        Err(get_error())
+    }
+}
+
+/// Copies the whole of `src` onto `dst` at (x, y), replacing the pixels
+/// underneath rather than blending with them.
+fn copy_at(src: &mut Surface, dst: &mut Surface, x: int, y: int) -> SdlResult<()> {
+    try!(src.set_blend_mode(BlendMode::BlendNone));
+    blit_at(src, dst, x, y)
+}
+
+/// Scales the alpha of a blended surface by the alpha of its foreground
+/// color, which SDL2_ttf only honors itself from 2.0.15 on.
+fn fade(surface: Surface, alpha: u8) -> Surface {
+    if alpha == 255 || linked_version_at_least(2, 0, 15) {
+        return surface;
+    }
+    let (w, h) = (surface.get_width() as uint, surface.get_height() as uint);
+    let pitch = surface.get_pitch() as uint;
+    surface.with_lock(|pixels| {
+        for y in range(0, h) {
+            for x in range(0, w) {
+                let p = y * pitch + x * 4 + ALPHA_BYTE;
+                pixels[p] = ((pixels[p] as uint * alpha as uint + 127) / 255) as u8;
+            }
+        }
+    });
+    surface
+}
+
+pub fn expand_tabs(text: &str, tab_width: uint) -> String {
+    //! Replace the tabs of text with spaces up to the next multiple of
+    //! tab_width columns, counting columns in chars from the start of each
+    //! line. A tab_width of 0 drops tabs. SDL2_ttf draws tabs as missing
+    //! glyphs, so expand them before rendering column aligned text.
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0u;
+    for ch in text.chars() {
+        match ch {
+            '\t' if tab_width > 0 => {
+                let spaces = tab_width - column % tab_width;
+                for _ in range(0, spaces) {
+                    expanded.push(' ');
+                }
+                column += spaces;
+            }
+            '\t' => {}
+            '\n' => {
+                expanded.push(ch);
+                column = 0;
+            }
+            _ => {
+                expanded.push(ch);
+                column += 1;
+            }
+        }
+    }
+    expanded
+}
+
+/// Draws `src` onto `dst` at (x, y), both ARGB8888, keeping the more
+/// opaque of each pair of pixels. Overlapping glyphs of one color thus
+/// merge instead of erasing or darkening each other.
+fn merge_at(src: &Surface, dst: &mut Surface, x: int, y: int) {
+    let (w, h) = (src.get_width(), src.get_height());
+    let (dst_w, dst_h) = (dst.get_width(), dst.get_height());
+    let (src_pitch, dst_pitch) = (src.get_pitch() as uint, dst.get_pitch() as uint);
+    src.with_lock(|src_pixels| {
+        dst.with_lock(|dst_pixels| {
+            for sy in range(0, h) {
+                for sx in range(0, w) {
+                    let (dx, dy) = (x + sx, y + sy);
+                    if dx < 0 || dy < 0 || dx >= dst_w || dy >= dst_h {
+                        continue;
+                    }
+                    let s = sy as uint * src_pitch + sx as uint * 4;
+                    let d = dy as uint * dst_pitch + dx as uint * 4;
+                    if src_pixels[s + ALPHA_BYTE] > dst_pixels[d + ALPHA_BYTE] {
+                        dst_pixels.slice_mut(d, d + 4).clone_from_slice(src_pixels.slice(s, s + 4));
+                    }
+                }
+            }
+        });
+    });
+}
+
+/// Draws `src` over `dst` at (x, y), both ARGB8888, compositing each
+/// pixel by the alpha of both: where `dst` is transparent the pixel of
+/// `src` is kept as it is, rather than blended towards black as a blit
+/// onto a transparent surface does.
+fn over_at(src: &Surface, dst: &mut Surface, x: int, y: int) {
+    let (w, h) = (src.get_width(), src.get_height());
+    let (dst_w, dst_h) = (dst.get_width(), dst.get_height());
+    let (src_pitch, dst_pitch) = (src.get_pitch() as uint, dst.get_pitch() as uint);
+    src.with_lock(|src_pixels| {
+        dst.with_lock(|dst_pixels| {
+            for sy in range(0, h) {
+                for sx in range(0, w) {
+                    let (dx, dy) = (x + sx, y + sy);
+                    if dx < 0 || dy < 0 || dx >= dst_w || dy >= dst_h {
+                        continue;
+                    }
+                    let s = sy as uint * src_pitch + sx as uint * 4;
+                    let d = dy as uint * dst_pitch + dx as uint * 4;
+                    // in 255ths: out = src + dst * (1 - src)
+                    let src_a = src_pixels[s + ALPHA_BYTE] as uint;
+                    let dst_a = dst_pixels[d + ALPHA_BYTE] as uint * (255 - src_a) / 255;
+                    let out_a = src_a + dst_a;
+                    if out_a == 0 {
+                        continue;
+                    }
+                    for &channel in [RED_BYTE, GREEN_BYTE, BLUE_BYTE].iter() {
+                        let mixed = src_pixels[s + channel] as uint * src_a
+                                  + dst_pixels[d + channel] as uint * dst_a;
+                        dst_pixels[d + channel] = ((mixed + out_a / 2) / out_a) as u8;
+                    }
+                    dst_pixels[d + ALPHA_BYTE] = out_a as u8;
+                }
+            }
+        });
+    });
+}
+
+bitflags!(
+    /// Font Style
+    ///
+    /// `StyleNormal` is the empty set, the same as `FontStyle::empty()`.
+    /// `bits()` gives the raw value to store, e.g. in a config file, and
+    /// `from_bits()` reads it back, returning None if any bit is not a
+    /// known style.
+    flags FontStyle : c_int {
     const StyleNormal = ffi::TTF_STYLE_NORMAL,
     const StyleBold   = ffi::TTF_STYLE_BOLD,
     const StyleItalic = ffi::TTF_STYLE_ITALIC,
@@ -62,16 +387,185 @@ bitflags!(flags FontStyle : c_int {
     const StyleStrikeThrough = ffi::TTF_STYLE_STRIKETHROUGH
 })
 
-#[deriving(Show, PartialEq, FromPrimitive)]
+// The single styles, in the order StyleIter yields them.
+static SINGLE_STYLES: [FontStyle, ..4] = [StyleBold, StyleItalic, StyleUnderline, StyleStrikeThrough];
+
+impl FontStyle {
+    pub fn iter_set(&self) -> StyleIter {
+        //! Iterate over the single styles set, e.g. to tick checkboxes.
+        StyleIter { styles: *self, next: 0 }
+    }
+
+    pub fn from_styles(styles: &[FontStyle]) -> FontStyle {
+        //! Combine styles into one set.
+        styles.iter().fold(StyleNormal, |set, &style| set | style)
+    }
+}
+
+/// Iterator over the single styles of a FontStyle.
+pub struct StyleIter {
+    styles: FontStyle,
+    next: uint
+}
+
+impl Iterator<FontStyle> for StyleIter {
+    fn next(&mut self) -> Option<FontStyle> {
+        while self.next < SINGLE_STYLES.len() {
+            let style = SINGLE_STYLES[self.next];
+            self.next += 1;
+            if self.styles.contains(style) {
+                return Some(style);
+            }
+        }
+        None
+    }
+}
+
+#[deriving(Clone, Copy, Show, PartialEq, FromPrimitive)]
 pub enum Hinting {
     HintingNormal = ffi::TTF_HINTING_NORMAL as int,
     HintingLight  = ffi::TTF_HINTING_LIGHT  as int,
     HintingMono   = ffi::TTF_HINTING_MONO   as int,
-    HintingNone   = ffi::TTF_HINTING_NONE   as int
+    HintingNone   = ffi::TTF_HINTING_NONE   as int,
+    /// Needs SDL2_ttf 2.0.18; older versions hint such fonts normally.
+    HintingLightSubpixel = ffi::TTF_HINTING_LIGHT_SUBPIXEL as int
+}
+
+impl Hinting {
+    pub fn to_ll(self) -> c_int {
+        //! Get the raw TTF_HINTING_* value.
+        self as c_int
+    }
+
+    pub fn to_str(self) -> &'static str {
+        //! Get the name of the setting, as parsed by from_str.
+        match self {
+            Hinting::HintingNormal        => "normal",
+            Hinting::HintingLight         => "light",
+            Hinting::HintingMono          => "mono",
+            Hinting::HintingNone          => "none",
+            Hinting::HintingLightSubpixel => "light_subpixel"
+        }
+    }
+}
+
+impl FromStr for Hinting {
+    fn from_str(s: &str) -> Option<Hinting> {
+        match s {
+            "normal"         => Some(Hinting::HintingNormal),
+            "light"          => Some(Hinting::HintingLight),
+            "mono"           => Some(Hinting::HintingMono),
+            "none"           => Some(Hinting::HintingNone),
+            "light_subpixel" => Some(Hinting::HintingLightSubpixel),
+            _                => None
+        }
+    }
+}
+
+impl Default for Hinting {
+    fn default() -> Hinting {
+        Hinting::HintingNormal
+    }
+}
+
+/// How text is rasterized.
+#[deriving(Clone, PartialEq, Show)]
+pub enum RenderMode {
+    /// Fast and aliased, on a transparent background.
+    Solid,
+    /// Antialiased over an opaque background of the given color.
+    Shaded(Color),
+    /// Antialiased with an alpha channel; the slowest.
+    Blended
+}
+
+/// LATIN1 (ISO-8859-1) encoded text, as taken by the *_bytes functions.
+/// Wrapping the bytes keeps UTF-8 from being passed to them by mistake.
+#[deriving(Clone, Copy, PartialEq, Show)]
+pub struct Latin1<'a>(pub &'a [u8]);
+
+/// Text in one of the encodings SDL2_ttf renders, for the generic
+/// render_solid, render_shaded and render_blended: `&str` is UTF8, `Latin1`
+/// is LATIN1 and `&[u16]` is UNICODE (UCS-2, native byte order).
+///
+/// Supporting another encoding takes one impl calling the matching
+/// TTF_Render* functions.
+pub trait RenderableText {
+    /// Whether there is nothing to draw.
+    fn is_empty_text(&self) -> bool;
+    /// Draws the text with the TTF_Render*_Solid function of its encoding.
+    unsafe fn render_solid_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface;
+    /// Draws the text with the TTF_Render*_Shaded function of its encoding.
+    unsafe fn render_shaded_ll(&self, font: *const TTF_Font, fg: SDL_Color,
+                               bg: SDL_Color) -> *const SDL_Surface;
+    /// Draws the text with the TTF_Render*_Blended function of its encoding.
+    unsafe fn render_blended_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface;
+}
+
+impl<'a> RenderableText for &'a str {
+    fn is_empty_text(&self) -> bool {
+        self.is_empty()
+    }
+    unsafe fn render_solid_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        self.with_c_str(|ctext| ffi::TTF_RenderUTF8_Solid(font, ctext, fg))
+    }
+    unsafe fn render_shaded_ll(&self, font: *const TTF_Font, fg: SDL_Color,
+                               bg: SDL_Color) -> *const SDL_Surface {
+        self.with_c_str(|ctext| ffi::TTF_RenderUTF8_Shaded(font, ctext, fg, bg))
+    }
+    unsafe fn render_blended_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        self.with_c_str(|ctext| ffi::TTF_RenderUTF8_Blended(font, ctext, fg))
+    }
+}
+
+impl<'a> RenderableText for Latin1<'a> {
+    fn is_empty_text(&self) -> bool {
+        let Latin1(bytes) = *self;
+        bytes.is_empty()
+    }
+    unsafe fn render_solid_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        let Latin1(bytes) = *self;
+        bytes.with_c_str(|ctext| ffi::TTF_RenderText_Solid(font, ctext, fg))
+    }
+    unsafe fn render_shaded_ll(&self, font: *const TTF_Font, fg: SDL_Color,
+                               bg: SDL_Color) -> *const SDL_Surface {
+        let Latin1(bytes) = *self;
+        bytes.with_c_str(|ctext| ffi::TTF_RenderText_Shaded(font, ctext, fg, bg))
+    }
+    unsafe fn render_blended_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        let Latin1(bytes) = *self;
+        bytes.with_c_str(|ctext| ffi::TTF_RenderText_Blended(font, ctext, fg))
+    }
+}
+
+/// Copies UCS-2 text with the NUL terminator SDL2_ttf expects.
+fn ucs2_with_nul(text: &[u16]) -> Vec<u16> {
+    let mut terminated = Vec::with_capacity(text.len() + 1);
+    terminated.push_all(text);
+    terminated.push(0);
+    terminated
+}
+
+impl<'a> RenderableText for &'a [u16] {
+    fn is_empty_text(&self) -> bool {
+        self.is_empty()
+    }
+    unsafe fn render_solid_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        ffi::TTF_RenderUNICODE_Solid(font, ucs2_with_nul(*self).as_ptr(), fg)
+    }
+    unsafe fn render_shaded_ll(&self, font: *const TTF_Font, fg: SDL_Color,
+                               bg: SDL_Color) -> *const SDL_Surface {
+        ffi::TTF_RenderUNICODE_Shaded(font, ucs2_with_nul(*self).as_ptr(), fg, bg)
+    }
+    unsafe fn render_blended_ll(&self, font: *const TTF_Font, fg: SDL_Color) -> *const SDL_Surface {
+        ffi::TTF_RenderUNICODE_Blended(font, ucs2_with_nul(*self).as_ptr(), fg)
+    }
 }
 
 /// Glyph Metrics
-#[deriving(PartialEq, Clone, Show)]
+///
+/// All values are whole pixels, as SDL2_ttf rounds those of FreeType.
+#[deriving(PartialEq, Clone, Default)]
 pub struct GlyphMetrics {
     pub minx: int,
     pub maxx: int,
@@ -80,6 +574,30 @@ pub struct GlyphMetrics {
     pub advance: int
 }
 
+impl GlyphMetrics {
+    pub fn width(&self) -> int {
+        //! Get the width of the glyph's bounding box.
+        self.maxx - self.minx
+    }
+
+    pub fn height(&self) -> int {
+        //! Get the height of the glyph's bounding box.
+        self.maxy - self.miny
+    }
+
+    pub fn bounding_box(&self) -> (int, int, int, int) {
+        //! Get the glyph's bounding box as (minx, miny, width, height).
+        (self.minx, self.miny, self.width(), self.height())
+    }
+}
+
+impl fmt::Show for GlyphMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "minx={} maxx={} miny={} maxy={} advance={}",
+               self.minx, self.maxx, self.miny, self.maxy, self.advance)
+    }
+}
+
 /// Returns the version of the dynamically linked SDL_ttf library
 pub fn get_linked_version() -> Version {
     unsafe {
@@ -87,83 +605,445 @@ pub fn get_linked_version() -> Version {
     }
 }
 
+/// Returns whether the dynamically linked SDL_ttf library is at least
+/// version major.minor.patch
+pub fn linked_version_at_least(major: u8, minor: u8, patch: u8) -> bool {
+    version_at_least(&get_linked_version(), major as int, minor as int, patch as int)
+}
+
+fn version_at_least(version: &Version, major: int, minor: int, patch: int) -> bool {
+    (version.major, version.minor, version.patch) >= (major, minor, patch)
+}
+
+/// Capabilities of the linked SDL_ttf library, judged by its version.
+///
+/// This binding targets SDL2_ttf 2.0 and wraps neither feature; the flags
+/// tell code calling into a newer library directly what it may rely on.
+#[deriving(Show)]
+pub struct Features {
+    pub version: Version,
+    /// HarfBuzz text shaping, enabled by default since SDL2_ttf 2.20.
+    pub harfbuzz: bool,
+    /// Signed distance field rendering, since SDL2_ttf 2.0.18.
+    pub sdf: bool
+}
+
+pub fn features() -> Features {
+    //! Query the capabilities of the dynamically linked SDL_ttf library.
+    let version = get_linked_version();
+    Features {
+        harfbuzz: version_at_least(&version, 2, 20, 0),
+        sdf: version_at_least(&version, 2, 0, 18),
+        version: version
+    }
+}
+
+// Whether init() holds a reference on the library, to be released by quit().
+static USER_INIT: AtomicBool = INIT_ATOMIC_BOOL;
+
+// Mirrors the byte swapping flag of SDL2_ttf, which cannot be read back.
+static BYTE_SWAPPED: AtomicBool = INIT_ATOMIC_BOOL;
+
+pub fn set_byte_swapped_unicode(swapped: bool) {
+    //! Set whether UNICODE text without a byte order mark is in the
+    //! opposite byte order of the host, for every font.
+    BYTE_SWAPPED.store(swapped, SeqCst);
+    unsafe { ffi::TTF_ByteSwappedUNICODE(swapped as c_int); }
+}
+
+pub fn byte_swapped_unicode() -> bool {
+    //! Get whether UNICODE text is taken to be byte swapped, as last set
+    //! by set_byte_swapped_unicode.
+    BYTE_SWAPPED.load(SeqCst)
+}
+
+/// A handle on the initialized truetype font API.
+///
+/// SDL2_ttf reference-counts `TTF_Init` and `TTF_Quit`, so the library stays
+/// initialized for as long as any context is alive. Every `Font` holds one,
+/// which guarantees it is closed before the library shuts down.
+pub struct Sdl2TtfContext {
+    _priv: ()
+}
+
+impl Sdl2TtfContext {
+    /// Takes another reference on an already initialized library.
+    fn retain() -> Sdl2TtfContext {
+        unsafe { ffi::TTF_Init(); }
+        Sdl2TtfContext { _priv: () }
+    }
+}
+
+impl Clone for Sdl2TtfContext {
+    fn clone(&self) -> Sdl2TtfContext {
+        Sdl2TtfContext::retain()
+    }
+}
+
+impl Drop for Sdl2TtfContext {
+    fn drop(&mut self) {
+        unsafe { ffi::TTF_Quit(); }
+    }
+}
+
+pub fn init_context() -> SdlResult<Sdl2TtfContext> {
+    //! Initialize the truetype font API, keeping it initialized until the
+    //! returned context and every font loaded meanwhile are dropped.
+    unsafe {
+        if ffi::TTF_Init() == 0 {
+            Ok(Sdl2TtfContext { _priv: () })
+        } else {
+            Err(get_error())
+        }
+    }
+}
+
+pub fn init_with_hints(hints: &[(&str, &str)]) -> SdlResult<Sdl2TtfContext> {
+    //! Set the given SDL hints, then initialize the truetype font API as
+    //! init_context does.
+    //!
+    //! SDL2_ttf itself reads no hints. FreeType, which it initializes here,
+    //! is tuned through the FREETYPE_PROPERTIES environment variable, e.g.
+    //! "truetype:interpreter-version=40" for the ClearType-style
+    //! interpreter; set it before calling this.
+    for &(name, value) in hints.iter() {
+        if !hint::set(name, value) {
+            return Err(format!("could not set hint {} to {}", name, value));
+        }
+    }
+    init_context()
+}
+
 pub fn init() -> bool {
-    //! Initialize the truetype font API.
+    //! Initialize the truetype font API. Calling it again before quit()
+    //! has no further effect.
+    if USER_INIT.load(SeqCst) {
+        return true;
+    }
     unsafe {
-        if ffi::TTF_WasInit() == 1 {
+        if ffi::TTF_Init() == 0 {
+            USER_INIT.store(true, SeqCst);
             true
         } else {
-            ffi::TTF_Init() == 0
+            false
         }
     }
 }
 
+// Guards the init() made by ensure_init().
+static ENSURE_INIT: Once = ONCE_INIT;
+
+pub fn ensure_init() -> SdlResult<()> {
+    //! Initialize the truetype font API on first use, from any number of
+    //! threads; init() runs exactly once however many of them race here.
+    //! quit() must not be called while any thread may still call this, as
+    //! it will not initialize the API again.
+    ENSURE_INIT.doit(|| { init(); });
+    check_inited()
+}
+
 pub fn was_inited() -> bool {
     //! Query the initilization status of the truetype font API.
     unsafe {
-        ffi::TTF_WasInit() == 1
+        ffi::TTF_WasInit() != 0
     }
 }
 
 pub fn quit() {
-    //! Shutdown and cleanup the truetype font API.
-    unsafe { ffi::TTF_Quit(); }
+    //! Shutdown and cleanup the truetype font API. Fonts that are still
+    //! alive keep the library initialized until they are dropped.
+    if USER_INIT.swap(false, SeqCst) {
+        unsafe { ffi::TTF_Quit(); }
+    }
+}
+
+/// A stable identifier of a loaded font, equal across reloads of the same
+/// face at the same size; usable as a HashMap key.
+///
+/// ptsize is the size the font was opened at, so fonts loaded with a DPI
+/// record the scaled size. Fonts loaded from memory or an RWops have a
+/// synthetic path, unique to each load.
+#[deriving(Clone, PartialEq, Eq, Hash, Show)]
+pub struct FontKey {
+    pub path: Path,
+    pub ptsize: int,
+    pub index: int
+}
+
+static NEXT_SYNTHETIC_KEY: AtomicUint = INIT_ATOMIC_UINT;
+
+impl FontKey {
+    fn synthetic(kind: &str, ptsize: int, index: int) -> FontKey {
+        let n = NEXT_SYNTHETIC_KEY.fetch_add(1, SeqCst);
+        FontKey { path: Path::new(format!("<{} {}>", kind, n)), ptsize: ptsize, index: index }
+    }
+}
+
+/// Where a font was loaded from.
+#[deriving(Clone, PartialEq, Show)]
+pub enum FontSource {
+    /// A face of a font file, which can be opened again, e.g. at another
+    /// size.
+    File(Path, int),
+    /// A buffer given to from_bytes, which the font keeps alive.
+    Memory,
+    /// An RWops, which cannot be read again once the font is closed.
+    RWops
 }
 
 /// The opaque holder of a loaded font.
-#[allow(raw_pointer_deriving)]
-#[deriving(PartialEq)]
-pub struct Font {
-    raw: *const ffi::TTF_Font,
-    owned: bool
+///
+/// The lifetime is that of the stream the font was loaded from, if any;
+/// fonts loaded from files are `Font<'static>`.
+///
+/// Rendered surfaces own their pixels, which are freed when the surface
+/// is dropped; copy them out to keep them longer.
+///
+/// A font keeps the library initialized for as long as it lives, yet its
+/// render methods still check that it is, failing with "SDL2_ttf not
+/// initialized" if it was shut down behind the font's back. Glyph heavy
+/// code can skip the check with the unsafe *_unchecked render methods.
+pub struct Font<'a> {
+    raw: *const TTF_Font,
+    owned: bool,
+    // the file of a font loaded from memory
+    data: Option<Arc<Vec<u8>>>,
+    // the stream of a font given its RWops, closed after the font
+    stream: Option<RWops>,
+    dpi: (u32, u32),
+    line_skip: Option<int>,
+    key: FontKey,
+    source: FontSource,
+    // released after the font is closed in drop()
+    _context: Sdl2TtfContext,
+    _marker: ContravariantLifetime<'a>
+}
+
+impl<'a> PartialEq for Font<'a> {
+    fn eq(&self, other: &Font<'a>) -> bool {
+        self.raw == other.raw
+    }
 }
 
-impl Drop for Font {
+impl<'a> Drop for Font<'a> {
     fn drop(&mut self) {
         if self.owned {
             unsafe {
-                // avoid close font after quit()
-                if ffi::TTF_WasInit() == 1 {
-                    ffi::TTF_CloseFont(self.raw);
-                }
+                ffi::TTF_CloseFont(self.raw);
             }
         }
     }
 }
 
-impl Font {
-    fn from_ll(raw: *const ffi::TTF_Font, owned: bool) -> Font {
-        Font { raw: raw, owned: owned }
+impl<'a> Font<'a> {
+    fn from_ll(raw: *const TTF_Font, owned: bool, key: FontKey, source: FontSource) -> Font<'a> {
+        Font { raw: raw, owned: owned, data: None, stream: None, dpi: (DEFAULT_DPI, DEFAULT_DPI),
+               line_skip: None, key: key, source: source, _context: Sdl2TtfContext::retain(), _marker: ContravariantLifetime }
     }
 
-    pub fn from_file(filename: &Path, ptsize: int) -> SdlResult<Font> {
-        //! Load file for use as a font, at ptsize size.
+    pub fn from_file<P: BytesContainer>(filename: P, ptsize: int) -> SdlResult<Font<'static>> {
+        //! Load file for use as a font, at ptsize size. The filename may
+        //! be anything a Path can be built from, e.g. &str or &Path.
+        //! Errors start with the path of the file.
+        try!(check_inited());
+        let path = try!(to_path(filename));
+        let c_ptsize: c_int = try!(to_c(ptsize, "point size"));
         unsafe {
-            let raw = ffi::TTF_OpenFont(filename.to_c_str().unwrap(), ptsize as c_int);
+            let raw = ffi::TTF_OpenFont(path.to_c_str().unwrap(), c_ptsize);
             if raw.is_null() {
-                Err(get_error())
+                Err(format!("{}: {}", path.display(), get_error()))
             } else {
-                Ok(Font { raw: raw, owned: true })
+                let source = FontSource::File(path.clone(), 0);
+                Ok(Font::from_ll(raw, true, FontKey { path: path, ptsize: ptsize, index: 0 }, source))
             }
         }
     }
 
-    pub fn from_file_index(filename: &Path, ptsize: int, index: int) -> SdlResult<Font> {
-        //! Load file, face index, for use as a font, at ptsize size.
+    pub fn from_file_styled<P: BytesContainer>(filename: P, ptsize: int, styles: FontStyle,
+                                               outline: int) -> SdlResult<Font<'static>> {
+        //! Load file for use as a font, at ptsize size, with the given
+        //! style and outline already set, so it is never seen unstyled.
+        let mut font = try!(Font::from_file(filename, ptsize));
+        font.set_style(styles);
+        font.set_outline(outline);
+        Ok(font)
+    }
+
+    pub fn from_file_index<P: BytesContainer>(filename: P, ptsize: int, index: int) -> SdlResult<Font<'static>> {
+        //! Load file, face index, for use as a font, at ptsize size. An
+        //! index past the faces of the file is reported as such.
+        try!(check_inited());
+        let path = try!(to_path(filename));
+        if index < 0 {
+            return Err(format!("{}: face index {} out of range", path.display(), index));
+        }
+        let c_ptsize: c_int = try!(to_c(ptsize, "point size"));
+        let c_index: c_long = try!(to_c(index, "face index"));
         unsafe {
-            let raw = ffi::TTF_OpenFontIndex(filename.to_c_str().unwrap(), ptsize as c_int, index as c_long);
+            let raw = ffi::TTF_OpenFontIndex(path.to_c_str().unwrap(), c_ptsize, c_index);
             if raw.is_null() {
-                Err(get_error())
+                let error = format!("{}: {}", path.display(), get_error());
+                if index == 0 {
+                    return Err(error);
+                }
+                // tell a bad index apart from a bad file by the first face
+                match Font::from_file(&path, ptsize) {
+                    Ok(ref first) if index >= first.faces() as int => {
+                        Err(format!("{}: face index {} out of range (font has {} faces)",
+                                    path.display(), index, first.faces()))
+                    }
+                    _ => Err(error)
+                }
             } else {
-                Ok(Font { raw: raw, owned: true })
+                let source = FontSource::File(path.clone(), index);
+                Ok(Font::from_ll(raw, true, FontKey { path: path, ptsize: ptsize, index: index }, source))
+            }
+        }
+    }
+
+    pub fn from_file_dpi<P: BytesContainer>(filename: P, ptsize: int,
+                                            hdpi: u32, vdpi: u32) -> SdlResult<Font<'static>> {
+        //! Load file for use as a font, at ptsize size for a display of
+        //! hdpi x vdpi resolution.
+        Font::from_file_index_dpi(filename, ptsize, 0, hdpi, vdpi)
+    }
+
+    pub fn from_file_index_dpi<P: BytesContainer>(filename: P, ptsize: int, index: int,
+                                                  hdpi: u32, vdpi: u32) -> SdlResult<Font<'static>> {
+        //! Load file, face index, for use as a font, at ptsize size for a
+        //! display of hdpi x vdpi resolution.
+        //!
+        //! SDL2_ttf 2.0 always renders at 72 DPI, so this opens the font at
+        //! the point size that yields the same pixel size. Glyphs are
+        //! scaled by vdpi only and keep their proportions.
+        let mut font = try!(Font::from_file_index(filename, scale_ptsize(ptsize, vdpi), index));
+        font.dpi = (hdpi, vdpi);
+        Ok(font)
+    }
+
+    pub fn from_bytes(data: Vec<u8>, ptsize: int) -> SdlResult<Font<'static>> {
+        //! Load an in-memory font file, e.g. one embedded with include_bin!,
+        //! at ptsize size. The font keeps the buffer alive until it is
+        //! closed.
+        try!(check_inited());
+        Font::from_shared_bytes(Arc::new(data), ptsize, 0)
+    }
+
+    fn from_shared_bytes(data: Arc<Vec<u8>>, ptsize: int, index: int) -> SdlResult<Font<'static>> {
+        let size: c_int = try!(to_c(data.len(), "font data length"));
+        let c_ptsize: c_int = try!(to_c(ptsize, "point size"));
+        let c_index: c_long = try!(to_c(index, "face index"));
+        let raw = unsafe {
+            let src = ffi::SDL_RWFromConstMem(data.as_ptr() as *const c_void, size);
+            if src.is_null() {
+                return Err(get_error());
             }
+            // freesrc, so SDL closes the stream along with the font
+            ffi::TTF_OpenFontIndexRW(src, 1, c_ptsize, c_index)
+        };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            let mut font = Font::from_ll(raw, true, FontKey::synthetic("memory", ptsize, index),
+                                         FontSource::Memory);
+            font.data = Some(data);
+            Ok(font)
+        }
+    }
+
+    /// The font handle for FFI calls, checking in debug builds that the
+    /// library it belongs to is still initialized.
+    #[inline]
+    fn ll(&self) -> *const TTF_Font {
+        debug_assert!(was_inited(), "font used after SDL2_ttf was shut down");
+        self.raw
+    }
+
+    pub unsafe fn raw(&self) -> *const TTF_Font {
+        //! Get the underlying TTF_Font, e.g. to reach its FreeType face from
+        //! other bindings. The font stays owned by self: the caller must not
+        //! close it, nor use it after self is dropped.
+        self.raw
+    }
+
+    pub fn key(&self) -> &FontKey {
+        //! Get the identifier of the file, face and size of the font.
+        &self.key
+    }
+
+    pub fn clone_at_size(&self, ptsize: int) -> SdlResult<Font<'static>> {
+        //! Open the face of this font again at ptsize size, with the same
+        //! DPI, style, outline, hinting and kerning. File fonts are read
+        //! again from their file and memory fonts share their buffer;
+        //! fonts loaded from an RWops cannot be reopened.
+        let (hdpi, vdpi) = self.dpi;
+        self.reopen(ptsize, hdpi, vdpi)
+    }
+
+    pub fn set_size(&mut self, ptsize: int) -> SdlResult<()> {
+        //! Change the point size of the font, by opening it again as
+        //! clone_at_size does. If that fails, the font is left as it was
+        //! and stays usable at its previous size.
+        let (hdpi, vdpi) = self.dpi;
+        self.set_size_dpi(ptsize, hdpi, vdpi)
+    }
+
+    pub fn set_size_dpi(&mut self, ptsize: int, hdpi: u32, vdpi: u32) -> SdlResult<()> {
+        //! Change the point size and resolution of the font, as set_size
+        //! does the point size.
+        let mut font = try!(self.reopen(ptsize, hdpi, vdpi));
+        // the old font is closed along with the new Font value
+        mem::swap(&mut self.raw, &mut font.raw);
+        mem::swap(&mut self.owned, &mut font.owned);
+        mem::swap(&mut self.data, &mut font.data);
+        mem::swap(&mut self.key, &mut font.key);
+        self.dpi = font.dpi;
+        Ok(())
+    }
+
+    fn reopen(&self, ptsize: int, hdpi: u32, vdpi: u32) -> SdlResult<Font<'static>> {
+        if ptsize <= 0 {
+            return Err(format!("invalid point size {}", ptsize));
         }
+        let mut font = match self.source {
+            FontSource::File(ref path, index) => {
+                try!(Font::from_file_index_dpi(path, ptsize, index, hdpi, vdpi))
+            }
+            FontSource::Memory => {
+                let data = self.data.as_ref().unwrap().clone();
+                let mut font = try!(Font::from_shared_bytes(data, scale_ptsize(ptsize, vdpi),
+                                                            self.key.index));
+                font.dpi = (hdpi, vdpi);
+                // the same buffer, so the same path
+                font.key.path = self.key.path.clone();
+                font
+            }
+            FontSource::RWops => {
+                return Err("fonts loaded from an RWops cannot be reopened".to_string());
+            }
+        };
+        font.set_style(self.get_style());
+        font.set_outline(self.get_outline());
+        font.set_hinting(self.get_hinting());
+        font.set_kerning(self.get_kerning());
+        Ok(font)
+    }
+
+    pub fn source(&self) -> &FontSource {
+        //! Get where the font was loaded from.
+        &self.source
+    }
+
+    pub fn dpi(&self) -> (u32, u32) {
+        //! Get the horizontal and vertical resolution the font was opened
+        //! for; 72 x 72 unless loaded with a DPI.
+        self.dpi
     }
 
     pub fn get_style(&self) -> FontStyle {
         //! Get font render style
         unsafe {
-            let raw = ffi::TTF_GetFontStyle(self.raw);
+            let raw = ffi::TTF_GetFontStyle(self.ll());
             FontStyle::from_bits_truncate(raw)
         }
     }
@@ -171,123 +1051,244 @@ impl Font {
     pub fn set_style(&mut self, styles: FontStyle) {
         //! Set font render style.
         unsafe {
-            ffi::TTF_SetFontStyle(self.raw, styles.bits())
+            ffi::TTF_SetFontStyle(self.ll(), styles.bits())
         }
     }
 
+    pub fn with_style<R>(&mut self, styles: FontStyle, f: |&mut Font<'a>| -> R) -> R {
+        //! Run f with the render style temporarily set to styles; the
+        //! previous style is restored afterwards.
+        let old_style = self.get_style();
+        self.set_style(styles);
+        let ret = f(self);
+        self.set_style(old_style);
+        ret
+    }
+
+    pub fn is_bold(&self) -> bool {
+        //! Get whether the bold style is set.
+        self.get_style().contains(StyleBold)
+    }
+
+    pub fn is_italic(&self) -> bool {
+        //! Get whether the italic style is set.
+        self.get_style().contains(StyleItalic)
+    }
+
+    pub fn is_underline(&self) -> bool {
+        //! Get whether the underline style is set.
+        self.get_style().contains(StyleUnderline)
+    }
+
+    pub fn is_strikethrough(&self) -> bool {
+        //! Get whether the strikethrough style is set.
+        self.get_style().contains(StyleStrikeThrough)
+    }
+
     pub fn get_outline(&self) -> int {
         //! Get font outline width.
         unsafe {
-            ffi::TTF_GetFontOutline(self.raw) as int
+            ffi::TTF_GetFontOutline(self.ll()) as int
         }
     }
 
     pub fn set_outline(&mut self, outline: int) {
-        //! Set font outline width.
+        //! Set font outline width, in pixels. Zero disables the outline;
+        //! negative widths are treated as zero.
+        let outline: c_int = num::cast(cmp::max(outline, 0)).unwrap_or(Bounded::max_value());
         unsafe {
-            ffi::TTF_SetFontOutline(self.raw, outline as c_int)
+            ffi::TTF_SetFontOutline(self.ll(), outline)
         }
     }
 
+    pub fn outline_padding(&self) -> int {
+        //! Get how far an outline extends glyphs past their metrics on
+        //! each side; rendered text grows by twice this in each direction.
+        //! This is the current outline width.
+        self.get_outline()
+    }
+
     pub fn get_hinting(&self) -> Hinting {
         //! Get freetype hinter setting.
         unsafe {
-            FromPrimitive::from_i32(ffi::TTF_GetFontHinting(self.raw)).unwrap()
+            FromPrimitive::from_i32(ffi::TTF_GetFontHinting(self.ll())).unwrap()
         }
     }
 
     pub fn set_hinting(&mut self, hinting: Hinting) {
         //! Set freetype hinter setting.
         unsafe {
-            ffi::TTF_SetFontHinting(self.raw, hinting as c_int)
+            ffi::TTF_SetFontHinting(self.ll(), hinting.to_ll())
         }
     }
 
     pub fn get_kerning(&self) -> bool {
         //! Get freetype kerning setting.
         unsafe {
-            ffi::TTF_GetFontKerning(self.raw) != 0
+            ffi::TTF_GetFontKerning(self.ll()) != 0
         }
     }
 
     pub fn set_kerning(&mut self, kerning: bool) {
         //! Set freetype kerning setting.
         unsafe {
-            ffi::TTF_SetFontKerning(self.raw, kerning as c_int)
+            ffi::TTF_SetFontKerning(self.ll(), kerning as c_int)
         }
     }
 
     pub fn height(&self) -> int {
-        //! Get font maximum total height.
+        //! Get font maximum total height, at its current size.
         unsafe {
-            ffi::TTF_FontHeight(self.raw) as int
+            ffi::TTF_FontHeight(self.ll()) as int
         }
     }
 
     pub fn ascent(&self) -> int {
-        //! Get font highest ascent (height above base).
+        //! Get font highest ascent (height above base), at its current size.
         unsafe {
-            ffi::TTF_FontAscent(self.raw) as int
+            ffi::TTF_FontAscent(self.ll()) as int
         }
     }
 
     pub fn descent(&self) -> int {
-        //! Get font lowest descent (height below base).
+        //! Get font lowest descent (height below base), at its current size.
         unsafe {
-            ffi::TTF_FontDescent(self.raw) as int
+            ffi::TTF_FontDescent(self.ll()) as int
         }
     }
 
-    pub fn line_skip(&self) -> int {
-        //! Get font recommended line spacing.
-        unsafe {
-            ffi::TTF_FontLineSkip(self.raw) as int
+    pub fn underline_position(&self) -> int {
+        //! Get the y offset from the top of rendered text at which to draw
+        //! an underline. SDL2_ttf 2.0 keeps the face's own value private,
+        //! so this approximates it as halfway down the descent.
+        self.ascent() - self.descent() / 2
+    }
+
+    pub fn underline_thickness(&self) -> int {
+        //! Get the thickness of an underline, in pixels. Like
+        //! underline_position, an approximation: a twentieth of the height,
+        //! and at least one pixel.
+        cmp::max(self.height() / 20, 1)
+    }
+
+    pub fn line_skip(&self) -> int {
+        //! Get font recommended line spacing, at its current size, or the
+        //! spacing given to set_line_skip.
+        match self.line_skip {
+            Some(skip) => skip,
+            None => unsafe { ffi::TTF_FontLineSkip(self.ll()) as int }
         }
     }
 
+    pub fn set_line_skip(&mut self, skip: int) {
+        //! Override the recommended line spacing.
+        //!
+        //! SDL2_ttf only gained TTF_SetFontLineSkip in 2.24, so the binding
+        //! keeps the override itself: line_skip() and this crate's
+        //! multi-line helpers use it, while the _wrapped renderers,
+        //! implemented by SDL, keep the font's own spacing.
+        self.line_skip = Some(skip);
+    }
+
+    fn check_usable(&self) -> SdlResult<()> {
+        try!(check_inited());
+        if self.raw.is_null() {
+            Err("font is null".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_height(&self) -> SdlResult<int> {
+        //! Get font maximum total height, failing instead of calling into
+        //! SDL if the library is not initialized or the font is null.
+        //! Fonts keep the library initialized themselves, so this only
+        //! guards against misuse, e.g. through raw().
+        try!(self.check_usable());
+        Ok(self.height())
+    }
+
+    pub fn try_ascent(&self) -> SdlResult<int> {
+        //! Get font highest ascent, checked as try_height is.
+        try!(self.check_usable());
+        Ok(self.ascent())
+    }
+
+    pub fn try_descent(&self) -> SdlResult<int> {
+        //! Get font lowest descent, checked as try_height is.
+        try!(self.check_usable());
+        Ok(self.descent())
+    }
+
+    pub fn try_line_skip(&self) -> SdlResult<int> {
+        //! Get font recommended line spacing, checked as try_height is.
+        try!(self.check_usable());
+        Ok(self.line_skip())
+    }
+
+    pub fn try_faces(&self) -> SdlResult<int> {
+        //! Get the number of faces in a font, checked as try_height is.
+        try!(self.check_usable());
+        Ok(self.faces())
+    }
+
+    pub fn set_variation(&mut self, name: &str) -> SdlResult<()> {
+        //! Select a named instance of a variable font. No SDL2_ttf release
+        //! exposes variation axes, so this always fails; to pick among the
+        //! faces of a collection instead, see FaceInfo::index_of_style.
+        Err(format!("cannot select variation {}: SDL2_ttf does not support variable fonts", name))
+    }
+
+    pub fn set_variation_axis(&mut self, axis_tag: &str, value: f32) -> SdlResult<()> {
+        //! Set a variation axis of a variable font, e.g. "wght" to 650.
+        //! Always fails, for the reason set_variation does.
+        Err(format!("cannot set variation axis {} to {}: SDL2_ttf does not support variable fonts",
+                    axis_tag, value))
+    }
+
+    pub fn get_variation_axes(&self) -> Vec<(String, f32, f32, f32)> {
+        //! Get the tag, minimum, default and maximum of each variation axis
+        //! of a variable font. Always empty, for the reason set_variation
+        //! fails.
+        Vec::new()
+    }
+
     pub fn faces(&self) -> int {
         //! Get the number of faces in a font.
         unsafe {
-            ffi::TTF_FontFaces(self.raw) as int
+            ffi::TTF_FontFaces(self.ll()) as int
         }
     }
 
     pub fn face_is_fixed_width(&self) -> bool {
         //! Get whether font is monospaced or not.
         unsafe {
-            ffi::TTF_FontFaceIsFixedWidth(self.raw) != 0
+            ffi::TTF_FontFaceIsFixedWidth(self.ll()) != 0
         }
     }
 
     pub fn face_family_name(&self) -> Option<String> {
         //! Get current font face family name string.
         unsafe {
-            // not owns buffer
-            let cname = ffi::TTF_FontFaceFamilyName(self.raw);
-            if cname.is_null() {
-                None
-            } else {
-                Some(CString::new(cname, false).as_str().unwrap().into_string())
-            }
+            name_from_ll(ffi::TTF_FontFaceFamilyName(self.ll()))
         }
     }
 
     pub fn face_style_name(&self) -> Option<String> {
         //! Get current font face style name string.
         unsafe {
-            let cname = ffi::TTF_FontFaceStyleName(self.raw);
-            if cname.is_null() {
-                None
-            } else {
-                Some(CString::new(cname, false).as_str().unwrap().into_string())
-            }
+            name_from_ll(ffi::TTF_FontFaceStyleName(self.ll()))
         }
     }
 
     pub fn index_of_char(&self, ch: char) -> Option<uint> {
         //! Get individual font glyph availability.
+        let code = match char_to_u16(ch) {
+            Some(code) => code,
+            None => return None
+        };
         unsafe {
-            let ret = ffi::TTF_GlyphIsProvided(self.raw, ch as u16);
+            let ret = ffi::TTF_GlyphIsProvided(self.ll(), code);
             if ret == 0 {
                 None
             } else {
@@ -296,15 +1297,67 @@ impl Font {
         }
     }
 
+    pub fn supports_str(&self, text: &str) -> bool {
+        //! Get whether the font provides a glyph for every character of
+        //! UTF8 text.
+        text.chars().all(|ch| self.index_of_char(ch).is_some())
+    }
+
+    pub fn missing_chars(&self, text: &str) -> Vec<char> {
+        //! Get the characters of UTF8 text the font has no glyph for, in
+        //! order of first appearance.
+        let mut missing = Vec::new();
+        for ch in text.chars() {
+            if self.index_of_char(ch).is_none() && !missing.contains(&ch) {
+                missing.push(ch);
+            }
+        }
+        missing
+    }
+
+    pub fn provided_chars(&self, first: char, last: char) -> Vec<char> {
+        //! Get every character from first to last (inclusive) that the
+        //! font provides a glyph for. SDL2_ttf only looks glyphs up by
+        //! 16-bit code, so the range stops at the end of the Basic
+        //! Multilingual Plane.
+        let last = cmp::min(last as u32, 0xFFFF);
+        range_inclusive(first as u32, last)
+            .filter_map(char::from_u32)
+            .filter(|&ch| self.index_of_char(ch).is_some())
+            .collect()
+    }
+
+    pub fn metrics_of_chars(&self, chars: &[char]) -> Vec<(char, Option<GlyphMetrics>)> {
+        //! Get the metrics of each of chars, in order, e.g. to build an
+        //! atlas.
+        chars.iter().map(|&ch| (ch, self.metrics_of_char(ch))).collect()
+    }
+
+    pub fn advance_of_char(&self, ch: char) -> Option<int> {
+        //! Get how far a glyph moves the pen, e.g. for laying out a grid of
+        //! cells. None for glyphs outside the Basic Multilingual Plane, as
+        //! SDL2_ttf 2.0 has no 32-bit metrics call.
+        //!
+        //! The advance is in whole pixels: SDL2_ttf rounds the 26.6 fixed
+        //! point advances of FreeType and offers no sub-pixel ones, so
+        //! justification spreading space over a long line has to correct
+        //! for the rounding itself.
+        self.metrics_of_char(ch).map(|metrics| metrics.advance)
+    }
+
     pub fn metrics_of_char(&self, ch: char) -> Option<GlyphMetrics> {
         //! Get individual font glyph metrics.
+        let code = match char_to_u16(ch) {
+            Some(code) => code,
+            None => return None
+        };
         let minx = 0;
         let maxx = 0;
         let miny = 0;
         let maxy = 0;
         let advance = 0;
         let ret = unsafe {
-            ffi::TTF_GlyphMetrics(self.raw, ch as u16,
+            ffi::TTF_GlyphMetrics(self.ll(), code,
                                   &minx, &maxx, &miny, &maxy, &advance)
         };
         if ret != 0 {
@@ -316,13 +1369,14 @@ impl Font {
         }
     }
 
-    pub fn size_of_bytes(&self, text: &[u8]) -> SdlResult<(int, int)> {
+    pub fn size_of_bytes(&self, text: Latin1) -> SdlResult<(int, int)> {
         //! Get size of LATIN1 text string as would be rendered.
+        let Latin1(bytes) = text;
         let w = 0;
         let h = 0;
         let ret = unsafe {
-            text.with_c_str(|ctext| {
-                    ffi::TTF_SizeText(self.raw, ctext, &w, &h)
+            bytes.with_c_str(|ctext| {
+                    ffi::TTF_SizeText(self.ll(), ctext, &w, &h)
                 })
         };
         if ret != 0 {
@@ -338,7 +1392,7 @@ impl Font {
         let h = 0;
         let ret = unsafe {
             text.with_c_str(|ctext| {
-                    ffi::TTF_SizeUTF8(self.raw, ctext, &w, &h)
+                    ffi::TTF_SizeUTF8(self.ll(), ctext, &w, &h)
                 })
         };
         if ret != 0 {
@@ -348,38 +1402,199 @@ impl Font {
         }
     }
 
-    pub fn render_bytes_solid(&self, text: &[u8], fg: Color) -> SdlResult<Surface> {
-        //! Draw LATIN1 text in solid mode.
-        unsafe {
-            let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderText_Solid(self.raw, ctext, color_to_c_color(fg))
-                });
-            if raw.is_null() {
-                Err(get_error())
-            } else {
-                Ok(Surface::from_ll(raw, true))
+    pub fn cursor_x(&self, text: &str, char_index: uint) -> SdlResult<int> {
+        //! Get the x offset of a caret placed after the first char_index
+        //! chars of UTF8 text. Indices past the end give the full width.
+        if char_index == 0 {
+            return Ok(0);
+        }
+        let prefix = match text.char_indices().nth(char_index) {
+            Some((byte_index, _)) => text.slice_to(byte_index),
+            None => text
+        };
+        let (w, _) = try!(self.size_of_str(prefix));
+        Ok(w)
+    }
+
+    pub fn kerning_delta(&mut self, text: &str) -> SdlResult<int> {
+        //! Get how many pixels kerning changes the width of UTF8 text by;
+        //! negative when it tightens it. The kerning setting is restored
+        //! afterwards, even if measuring fails.
+        let old_kerning = self.get_kerning();
+        self.set_kerning(true);
+        let kerned = self.size_of_str(text);
+        self.set_kerning(false);
+        let unkerned = self.size_of_str(text);
+        self.set_kerning(old_kerning);
+
+        let (kerned, _) = try!(kerned);
+        let (unkerned, _) = try!(unkerned);
+        Ok(kerned - unkerned)
+    }
+
+    pub fn has_ligature(&self, a: char, b: char) -> SdlResult<bool> {
+        //! Query whether shaping joins a and b into a ligature, e.g. "fi",
+        //! judged by whether the pair measures differently once a zero
+        //! width non-joiner keeps them apart. Only SDL2_ttf built with
+        //! HarfBuzz shapes text, so without it this is always false.
+        if !features().harfbuzz {
+            return Ok(false);
+        }
+        let mut joined = String::with_capacity(8);
+        joined.push(a);
+        joined.push(b);
+        let mut apart = String::with_capacity(8);
+        apart.push(a);
+        apart.push('\u200C');
+        apart.push(b);
+        let (joined_w, _) = try!(self.size_of_str(joined.as_slice()));
+        let (apart_w, _) = try!(self.size_of_str(apart.as_slice()));
+        Ok(joined_w != apart_w)
+    }
+
+    pub fn rect_of_str(&self, text: &str, x: i32, y: i32) -> SdlResult<Rect> {
+        //! Get the rect UTF8 text would occupy if rendered at (x, y).
+        let (w, h) = try!(self.size_of_str(text));
+        Ok(Rect::new(x, y, w as i32, h as i32))
+    }
+
+    pub fn layout<'b>(&self, text: &'b str) -> SdlResult<Vec<(&'b str, Rect)>> {
+        //! Get where each char of a line of UTF8 text would be drawn,
+        //! relative to the top-left of the line, advancing by the glyph
+        //! metrics and by kerning if enabled. Each rect is the size
+        //! size_of_str gives the char, so rendering it on its own fills it.
+        //! Chars the font lacks are left out, but still advance the pen.
+        //!
+        //! A combining mark is kept with the char before it: the pair
+        //! comes as one piece of text, to render as one string so that SDL
+        //! places the mark, with a rect covering both. Other multi-char
+        //! graphemes, e.g. flags, are laid out char by char.
+        try!(check_inited());
+        let mut pen = Pen::new(self, 0);
+        let clusters = split_clusters(text);
+        let mut glyphs = Vec::with_capacity(clusters.len());
+        for &cluster in clusters.iter() {
+            let mut chars = cluster.chars();
+            let base = chars.next().unwrap();
+            let x = pen.place(self, base);
+            for ch in chars {
+                pen.place(self, ch);
+            }
+            if self.index_of_char(base).is_some() {
+                let (w, h) = try!(self.size_of_str(cluster));
+                glyphs.push((cluster, Rect::new(x as i32, 0, w as i32, h as i32)));
             }
         }
+        Ok(glyphs)
     }
 
-    pub fn render_str_solid(&self, text: &str, fg: Color) -> SdlResult<Surface> {
-        //! Draw UTF8 text in solid mode.
+    fn kerning_between(&self, prev_index: uint, index: uint) -> int {
         unsafe {
-            let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderUTF8_Solid(self.raw, ctext, color_to_c_color(fg))
-                });
-            if raw.is_null() {
-                Err(get_error())
-            } else {
-                Ok(Surface::from_ll(raw, true))
-            }
+            ffi::TTF_GetFontKerningSize(self.ll(), prev_index as c_int, index as c_int) as int
+        }
+    }
+
+    fn render_empty(&self) -> SdlResult<Surface> {
+        // SDL refuses to render zero width text; give every mode a
+        // transparent surface of no width and the height of the font
+        new_argb_surface(0, self.height())
+    }
+
+    pub fn render_solid<T: RenderableText, C: ToColor>(&self, text: T, fg: C) -> SdlResult<Surface> {
+        //! Draw text of any encoding in solid mode.
+        try!(check_inited());
+        if text.is_empty_text() {
+            return self.render_empty();
+        }
+        let raw = unsafe {
+            text.render_solid_ll(self.ll(), color_to_c_color(fg.to_color()))
+        };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(Surface::from_ll(raw, true))
+        }
+    }
+
+    pub fn render_shaded<T: RenderableText, C: ToColor, D: ToColor>(&self, text: T, fg: C, bg: D) -> SdlResult<Surface> {
+        //! Draw text of any encoding in shaded mode.
+        try!(check_inited());
+        if text.is_empty_text() {
+            return self.render_empty();
+        }
+        let raw = unsafe {
+            text.render_shaded_ll(self.ll(), color_to_c_color(fg.to_color()), color_to_c_color(bg.to_color()))
+        };
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(Surface::from_ll(raw, true))
+        }
+    }
+
+    pub fn render_blended<T: RenderableText, C: ToColor>(&self, text: T, fg: C) -> SdlResult<Surface> {
+        //! Draw text of any encoding in blended mode. The alpha of fg
+        //! scales that of the glyphs.
+        try!(check_inited());
+        unsafe { self.render_blended_unchecked(text, fg) }
+    }
+
+    pub unsafe fn render_blended_unchecked<T: RenderableText, C: ToColor>(&self, text: T, fg: C) -> SdlResult<Surface> {
+        //! Draw text of any encoding in blended mode, as render_blended
+        //! does but without first checking that SDL2_ttf is initialized,
+        //! which saves a call into SDL per render; see benches/render.rs.
+        //! Only safe while nothing outside this crate, e.g. a direct
+        //! TTF_Quit call, can have shut the library down.
+        if text.is_empty_text() {
+            return self.render_empty();
+        }
+        let fg = color_to_c_color(fg.to_color());
+        let raw = text.render_blended_ll(self.raw, fg);
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(fade(Surface::from_ll(raw, true), fg.a))
         }
     }
 
-    pub fn render_char_solid(&self, ch: char, fg: Color) -> SdlResult<Surface> {
+    pub fn render_bytes_solid<C: ToColor>(&self, text: Latin1, fg: C) -> SdlResult<Surface> {
+        //! Draw LATIN1 text in solid mode.
+        self.render_solid(text, fg)
+    }
+
+    pub fn render_str_solid<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in solid mode.
+        self.render_solid(text, fg)
+    }
+
+    pub fn render_unicode_be<C: ToColor>(&self, text: &[u16], fg: C) -> SdlResult<Surface> {
+        //! Draw big endian UNICODE text in blended mode, whatever the byte
+        //! order of the host. The byte swapping flag is set for the render
+        //! and restored afterwards, even if it fails; it is global, so this
+        //! must not race with other UNICODE renders.
+        self.render_unicode_in_order(text, fg, cfg!(target_endian = "little"))
+    }
+
+    pub fn render_unicode_le<C: ToColor>(&self, text: &[u16], fg: C) -> SdlResult<Surface> {
+        //! Draw little endian UNICODE text in blended mode, as
+        //! render_unicode_be does big endian text.
+        self.render_unicode_in_order(text, fg, cfg!(target_endian = "big"))
+    }
+
+    fn render_unicode_in_order<C: ToColor>(&self, text: &[u16], fg: C, swapped: bool) -> SdlResult<Surface> {
+        let old_swapped = byte_swapped_unicode();
+        set_byte_swapped_unicode(swapped);
+        let surface = self.render_blended(text, fg);
+        set_byte_swapped_unicode(old_swapped);
+        surface
+    }
+
+    pub fn render_char_solid<C: ToColor>(&self, ch: char, fg: C) -> SdlResult<Surface> {
         //! Draw a UNICODE glyph in solid mode.
+        try!(check_inited());
+        let code = try!(glyph_code(ch));
         unsafe {
-            let raw = ffi::TTF_RenderGlyph_Solid(self.raw, ch as u16, color_to_c_color(fg));
+            let raw = ffi::TTF_RenderGlyph_Solid(self.ll(), code, color_to_c_color(fg.to_color()));
             if raw.is_null() {
                 Err(get_error())
             } else {
@@ -388,38 +1603,36 @@ impl Font {
         }
     }
 
-    pub fn render_bytes_shaded(&self, text: &[u8], fg: Color, bg: Color) -> SdlResult<Surface> {
+    pub fn render_bytes_shaded<C: ToColor, D: ToColor>(&self, text: Latin1, fg: C, bg: D) -> SdlResult<Surface> {
         //! Draw LATIN1 text in shaded mode.
-        unsafe {
-            let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderText_Shaded(self.raw, ctext, color_to_c_color(fg), color_to_c_color(bg))
-                });
-            if raw.is_null() {
-                Err(get_error())
-            } else {
-                Ok(Surface::from_ll(raw, true))
-            }
-        }
+        self.render_shaded(text, fg, bg)
     }
 
-    pub fn render_str_shaded(&self, text: &str, fg: Color, bg: Color) -> SdlResult<Surface> {
+    pub fn render_str_shaded<C: ToColor, D: ToColor>(&self, text: &str, fg: C, bg: D) -> SdlResult<Surface> {
         //! Draw UTF8 text in shaded mode.
-        unsafe {
-            let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderUTF8_Shaded(self.raw, ctext, color_to_c_color(fg), color_to_c_color(bg))
-                });
-            if raw.is_null() {
-                Err(get_error())
-            } else {
-                Ok(Surface::from_ll(raw, true))
-            }
+        self.render_shaded(text, fg, bg)
+    }
+
+    pub fn render_str_shaded_transparent_bg<C: ToColor, D: ToColor>(&self, text: &str, fg: C, bg: D) -> SdlResult<Surface> {
+        //! Draw UTF8 text in shaded mode, with bg set as the surface's
+        //! colorkey so the background is transparent when blitted. Fails
+        //! if fg and bg have the same RGB, as the text would vanish too.
+        let (fg, bg) = (fg.to_color(), bg.to_color());
+        let (f, b) = (color_to_c_color(fg.clone()), color_to_c_color(bg.clone()));
+        if (f.r, f.g, f.b) == (b.r, b.g, b.b) {
+            return Err("foreground and background colors are the same".to_string());
         }
+        let mut surface = try!(self.render_str_shaded(text, fg, bg.clone()));
+        try!(surface.set_color_key(true, bg));
+        Ok(surface)
     }
 
-    pub fn render_char_shaded(&self, ch: char, fg: Color, bg: Color) -> SdlResult<Surface> {
+    pub fn render_char_shaded<C: ToColor, D: ToColor>(&self, ch: char, fg: C, bg: D) -> SdlResult<Surface> {
         //! Draw a UNICODE glyph in shaded mode.
+        try!(check_inited());
+        let code = try!(glyph_code(ch));
         unsafe {
-            let raw = ffi::TTF_RenderGlyph_Shaded(self.raw, ch as u16, color_to_c_color(fg), color_to_c_color(bg));
+            let raw = ffi::TTF_RenderGlyph_Shaded(self.ll(), code, color_to_c_color(fg.to_color()), color_to_c_color(bg.to_color()));
             if raw.is_null() {
                 Err(get_error())
             } else {
@@ -428,75 +1641,1129 @@ impl Font {
         }
     }
 
-    pub fn render_bytes_blended(&self, text: &[u8], fg: Color) -> SdlResult<Surface> {
+    pub fn render_bytes_blended<C: ToColor>(&self, text: Latin1, fg: C) -> SdlResult<Surface> {
         //! Draw LATIN1 text in blended mode.
+        self.render_blended(text, fg)
+    }
+
+    pub fn render_str_blended<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode. The alpha of fg scales that of
+        //! the glyphs, e.g. for fading text.
+        self.render_blended(text, fg)
+    }
+
+    pub fn render_utf8_bytes_blended<C: ToColor>(&self, bytes: &[u8], fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text held in bytes in blended mode, failing if they
+        //! are not valid UTF8. Unlike String::from_utf8, nothing is copied.
+        match str::from_utf8(bytes) {
+            Some(text) => self.render_str_blended(text, fg),
+            None => Err("text is not valid UTF8".to_string())
+        }
+    }
+
+    pub fn render_str_blended_premultiplied<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode, with the color channels of each
+        //! pixel premultiplied by its alpha. The surface stays ARGB8888.
+        let surface = try!(self.render_str_blended(text, fg));
+        let (w, h) = (surface.get_width() as uint, surface.get_height() as uint);
+        let pitch = surface.get_pitch() as uint;
+        surface.with_lock(|pixels| {
+            for y in range(0, h) {
+                for x in range(0, w) {
+                    let p = y * pitch + x * 4;
+                    let alpha = pixels[p + ALPHA_BYTE] as uint;
+                    for &channel in [RED_BYTE, GREEN_BYTE, BLUE_BYTE].iter() {
+                        pixels[p + channel] = ((pixels[p + channel] as uint * alpha + 127) / 255) as u8;
+                    }
+                }
+            }
+        });
+        Ok(surface)
+    }
+
+    pub fn fits_and_render_blended<C: ToColor>(&self, text: &str, fg: C, max_w: int) -> SdlResult<Option<Surface>> {
+        //! Draw UTF8 text in blended mode if it is at most max_w pixels
+        //! wide; None, without rendering, if it is wider.
+        let (w, _) = try!(self.size_of_str(text));
+        if w > max_w {
+            Ok(None)
+        } else {
+            self.render_str_blended(text, fg).map(Some)
+        }
+    }
+
+    pub fn render_str_blended_with_baseline<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<(Surface, int)> {
+        //! Draw UTF8 text in blended mode and get the y offset of its
+        //! baseline from the top of the surface, for lining up runs of
+        //! different fonts or sizes. The baseline is the font's ascent.
+        let surface = try!(self.render_str_blended(text, fg));
+        Ok((surface, self.ascent()))
+    }
+
+    pub fn update_streaming_texture<C: ToColor>(&self, texture: &mut Texture, text: &str, fg: C) -> SdlResult<()> {
+        //! Draw UTF8 text in blended mode into the top-left of a streaming
+        //! ARGB8888 texture, reusing it instead of creating a texture each
+        //! time the text changes. The rest of the texture is cleared. Fails
+        //! if the texture is of another kind or too small for the text.
+        let query = try!(texture.query());
+        if query.access != TextureAccess::AccessStreaming {
+            return Err("texture is not a streaming texture".to_string());
+        }
+        if query.format != pixels::PixelFormatFlag::ARGB8888 {
+            return Err("texture is not in the ARGB8888 format".to_string());
+        }
+        let surface = try!(self.render_str_blended(text, fg));
+        let (w, h) = (surface.get_width(), surface.get_height());
+        if w > query.width || h > query.height {
+            return Err(format!("{}x{} text does not fit in a {}x{} texture",
+                               w, h, query.width, query.height));
+        }
+        let src_pitch = surface.get_pitch() as uint;
+        let row = w as uint * 4;
+        texture.with_lock(None, |dst, dst_pitch| {
+            let dst_pitch = dst_pitch as uint;
+            for b in dst.iter_mut() {
+                *b = 0;
+            }
+            surface.with_lock(|src| {
+                for y in range(0, h as uint) {
+                    let (s, d) = (y * src_pitch, y * dst_pitch);
+                    dst.slice_mut(d, d + row).clone_from_slice(src.slice(s, s + row));
+                }
+            });
+        })
+    }
+
+    pub unsafe fn render_str_blended_unowned<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<*const SDL_Surface> {
+        //! Draw UTF8 text in blended mode into a surface that nothing in
+        //! Rust owns, e.g. to hand it to a C library that frees it. The
+        //! caller is responsible for freeing it with SDL_FreeSurface
+        //! exactly once; otherwise it leaks.
+        let surface = try!(self.render_str_blended(text, fg));
+        let raw = surface.raw();
+        mem::forget(surface);
+        Ok(raw)
+    }
+
+    pub fn render_str_blended_into_owned_vec<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<(Vec<u8>, int, int)> {
+        //! Draw UTF8 text in blended mode and copy the pixels out as RGBA8
+        //! bytes owned by the caller, as rgba_pixels does. Also returns the
+        //! width and height.
+        let surface = try!(self.render_str_blended(text, fg));
+        rgba_pixels(&surface)
+    }
+
+    pub fn render_char_blended<C: ToColor>(&self, ch: char, fg: C) -> SdlResult<Surface> {
+        //! Draw a UNICODE glyph in blended mode.
+        try!(check_inited());
+        unsafe { self.render_char_blended_unchecked(ch, fg) }
+    }
+
+    pub unsafe fn render_char_blended_unchecked<C: ToColor>(&self, ch: char, fg: C) -> SdlResult<Surface> {
+        //! Draw a UNICODE glyph in blended mode, as render_char_blended
+        //! does but without the init check; the same caveats as for
+        //! render_blended_unchecked apply.
+        let fg = color_to_c_color(fg.to_color());
+        let code = try!(glyph_code(ch));
+        let raw = ffi::TTF_RenderGlyph_Blended(self.raw, code, fg);
+        if raw.is_null() {
+            Err(get_error())
+        } else {
+            Ok(fade(Surface::from_ll(raw, true), fg.a))
+        }
+    }
+
+    pub fn render_char_alpha_mask(&self, ch: char) -> SdlResult<(Vec<u8>, int, int)> {
+        //! Draw a UNICODE glyph in blended mode and copy out its coverage,
+        //! one alpha byte a pixel, row by row without padding, for drawing
+        //! into buffers other than SDL surfaces. Also returns the width and
+        //! height. As SDL2_ttf 2.0 has no 32-bit glyph functions, ch must
+        //! lie in the Basic Multilingual Plane.
+        let glyph = try!(self.render_char_blended(ch, (255, 255, 255)));
+        let (w, h) = (glyph.get_width() as uint, glyph.get_height() as uint);
+        let pitch = glyph.get_pitch() as uint;
+        let mut mask = Vec::with_capacity(w * h);
+        glyph.with_lock(|pixels| {
+            for y in range(0, h) {
+                for x in range(0, w) {
+                    mask.push(pixels[y * pitch + x * 4 + ALPHA_BYTE]);
+                }
+            }
+        });
+        Ok((mask, w as int, h as int))
+    }
+
+    pub fn render_char_blended_or<C: ToColor>(&self, ch: char, replacement: char, fg: C) -> SdlResult<Surface> {
+        //! Draw a UNICODE glyph in blended mode, or the replacement glyph
+        //! if the font lacks it rather than its missing glyph box.
+        match self.index_of_char(ch) {
+            Some(_) => self.render_char_blended(ch, fg),
+            None => self.render_char_blended(replacement, fg)
+        }
+    }
+
+    pub fn render_char_blended_with_metrics<C: ToColor>(&self, ch: char, fg: C) -> SdlResult<(Surface, GlyphMetrics)> {
+        //! Draw a UNICODE glyph in blended mode and get its metrics, failing
+        //! as a whole if either does. SDL2_ttf 2.0 has no 32-bit glyph
+        //! functions, so ch must lie in the Basic Multilingual Plane.
+        try!(glyph_code(ch));
+        let metrics = match self.metrics_of_char(ch) {
+            Some(metrics) => metrics,
+            None => return Err(get_error())
+        };
+        let surface = try!(self.render_char_blended(ch, fg));
+        Ok((surface, metrics))
+    }
+
+    pub fn render_bytes_blended_wrapped<C: ToColor>(&self, text: Latin1, fg: C, wrap_length: u32) -> SdlResult<Surface> {
+        //! Draw LATIN1 text in blended mode, wrapped at spaces and newlines
+        //! into lines of at most wrap_length pixels.
+        let Latin1(bytes) = text;
+        try!(check_inited());
+        if bytes.is_empty() {
+            return self.render_empty();
+        }
+        let fg = color_to_c_color(fg.to_color());
         unsafe {
-            let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderText_Blended(self.raw, ctext, color_to_c_color(fg))
+            let raw = bytes.with_c_str(|ctext| {
+                    ffi::TTF_RenderText_Blended_Wrapped(self.ll(), ctext, fg, wrap_length)
                 });
             if raw.is_null() {
                 Err(get_error())
             } else {
-                Ok(Surface::from_ll(raw, true))
+                Ok(fade(Surface::from_ll(raw, true), fg.a))
             }
         }
     }
 
-    pub fn render_str_blended(&self, text: &str, fg: Color) -> SdlResult<Surface> {
-        //! Draw UTF8 text in blended mode.
+    pub fn render_str_blended_wrapped<C: ToColor>(&self, text: &str, fg: C, wrap_length: u32) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode, wrapped at spaces and newlines
+        //! into lines of at most wrap_length pixels.
+        try!(check_inited());
+        if text.is_empty() {
+            return self.render_empty();
+        }
+        let fg = color_to_c_color(fg.to_color());
         unsafe {
             let raw = text.with_c_str(|ctext| {
-                    ffi::TTF_RenderUTF8_Blended(self.raw, ctext, color_to_c_color(fg))
+                    ffi::TTF_RenderUTF8_Blended_Wrapped(self.ll(), ctext, fg, wrap_length)
                 });
             if raw.is_null() {
                 Err(get_error())
             } else {
-                Ok(Surface::from_ll(raw, true))
+                Ok(fade(Surface::from_ll(raw, true), fg.a))
             }
         }
     }
 
-    pub fn render_char_blended(&self, ch: char, fg: Color) -> SdlResult<Surface> {
-        //! Draw a UNICODE glyph in blended mode.
-        unsafe {
-            let raw = ffi::TTF_RenderGlyph_Blended(self.raw, ch as u16, color_to_c_color(fg));
-            if raw.is_null() {
-                Err(get_error())
-            } else {
-                Ok(Surface::from_ll(raw, true))
+    pub fn render_str_blended_wrapped_ex<C: ToColor>(&self, text: &str, fg: C, wrap_length: u32,
+                                                   tab_width: uint) -> SdlResult<Surface> {
+        //! Draw UTF8 text as render_str_blended_wrapped does, with tabs
+        //! expanded to spaces up to the next multiple of tab_width columns.
+        //! Columns count chars from each newline, so they align in a
+        //! fixed width font and on lines that are not soft wrapped.
+        self.render_str_blended_wrapped(expand_tabs(text, tab_width).as_slice(), fg, wrap_length)
+    }
+
+    pub fn render_str_blended_wrapped_to_texture<'r, C: ToColor>(&self, renderer: &'r Renderer, text: &str, fg: C,
+                                                               wrap_length: u32) -> SdlResult<TextTexture<'r>> {
+        //! Draw UTF8 text as render_str_blended_wrapped does, and upload it
+        //! as a texture of renderer, e.g. for a dialogue box.
+        let surface = try!(self.render_str_blended_wrapped(text, fg, wrap_length));
+        let texture = try!(renderer.create_texture_from_surface(&surface));
+        Ok(TextTexture { texture: texture, _renderer: renderer })
+    }
+
+    pub fn suggest_wrap_width(&self, text: &str, target_ratio: f32) -> SdlResult<u32> {
+        //! Estimate the wrap length that makes UTF8 text rendered by the
+        //! wrapped functions come out about target_ratio times as wide as
+        //! it is high.
+        if !(target_ratio > 0.0) {
+            return Err(format!("invalid target ratio {}", target_ratio));
+        }
+        let (w, _) = try!(self.size_of_str(text));
+        // wrapping at W gives about w / W lines of line_skip() pixels, so
+        // W / (w / W * line_skip()) = ratio when W = sqrt(ratio * w * line_skip())
+        let wrap = (target_ratio * w as f32 * self.line_skip() as f32).sqrt();
+        Ok(cmp::max(1, cmp::min(w, wrap.ceil() as int)) as u32)
+    }
+
+    pub fn wrapped_height(&self, text: &str, wrap_length: u32) -> SdlResult<int> {
+        //! Get the height UTF8 text wrapped into lines of at most
+        //! wrap_length pixels would take, line_skip() apart, as drawn by
+        //! render_str_blended_lines and render_str_blended_ellipsized.
+        //! Empty text is one line high.
+        //!
+        //! This does not size the output of the *_wrapped renderers: SDL
+        //! breaks their lines by rules of its own and spaces them by the
+        //! font's line skip, ignoring set_line_skip.
+        let lines = try!(self.wrap_lines(text, wrap_length));
+        Ok((lines.len() as int - 1) * self.line_skip() + self.height())
+    }
+
+    /// Splits text into lines: at each newline, and at the last space
+    /// before a line grows wider than wrap_length. A word too wide on its
+    /// own gets a line to itself. SDL's own wrapping may break differently.
+    fn wrap_lines<'b>(&self, text: &'b str, wrap_length: u32) -> SdlResult<Vec<&'b str>> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut start = 0;
+            let mut fit = None;
+            let breaks = paragraph.char_indices()
+                .filter(|&(_, ch)| ch == ' ')
+                .map(|(i, _)| i)
+                .chain(Some(paragraph.len()).into_iter());
+            for end in breaks {
+                let (w, _) = try!(self.size_of_str(paragraph.slice(start, end)));
+                if w as u32 > wrap_length {
+                    match fit {
+                        Some(fit) => {
+                            lines.push(paragraph.slice(start, fit));
+                            // the space broken at is dropped
+                            start = fit + 1;
+                        }
+                        None => {}
+                    }
+                }
+                fit = Some(end);
+            }
+            lines.push(paragraph.slice(start, fit.unwrap_or(paragraph.len())));
+        }
+        Ok(lines)
+    }
+
+    pub fn render_str_blended_lines<C: ToColor>(&self, text: &str, fg: C, wrap_length: u32) -> SdlResult<Vec<Surface>> {
+        //! Draw UTF8 text in blended mode, wrapped as wrapped_height
+        //! measures it, with each line on its own surface, e.g. to draw only
+        //! the lines in view. Lines are meant to be line_skip() apart.
+        let lines = try!(self.wrap_lines(text, wrap_length));
+        let mut surfaces = Vec::with_capacity(lines.len());
+        for line in lines.iter() {
+            surfaces.push(try!(self.render_str_blended(*line, &fg)));
+        }
+        Ok(surfaces)
+    }
+
+    pub fn render_str_blended_ellipsized<C: ToColor>(&self, text: &str, fg: C, wrap_length: u32,
+                                                   max_lines: uint) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode, wrapped as wrapped_height
+        //! measures it, in at most max_lines lines. If the text needs more,
+        //! the last line shown is cut short, keeping combining marks with
+        //! their base char, to end with an ellipsis within wrap_length.
+        let wrapped = try!(self.wrap_lines(text, wrap_length));
+        let mut lines: Vec<String> = wrapped.iter().take(max_lines).map(|line| line.to_string()).collect();
+        if wrapped.len() > max_lines && max_lines > 0 {
+            let last = lines.pop().unwrap();
+            let mut end = last.len();
+            loop {
+                let candidate = format!("{}\u2026", last.slice_to(end));
+                let (w, _) = try!(self.size_of_str(candidate.as_slice()));
+                if w as u32 <= wrap_length || end == 0 {
+                    lines.push(candidate);
+                    break;
+                }
+                // drop the last char along with any marks combining with it
+                loop {
+                    let (start, ch) = last.slice_to(end).char_indices().last().unwrap();
+                    end = start;
+                    if end == 0 || !is_combining_mark(ch) {
+                        break;
+                    }
+                }
+            }
+        }
+        let styled: Vec<(&str, &C)> = lines.iter().map(|line| (line.as_slice(), &fg)).collect();
+        self.render_lines_blended(styled.as_slice(), 0)
+    }
+
+    pub fn render_str_blended_onto<C: ToColor>(&self, dest: &mut Surface, rect: Rect,
+                                               text: &str, fg: C) -> SdlResult<()> {
+        //! Draw UTF8 text in blended mode onto dest at the top-left of rect,
+        //! clipped to rect.
+        let surface = try!(self.render_str_blended(text, fg));
+        let src_rect = Rect::new(0, 0, cmp::min(surface.get_width() as i32, rect.w),
+                                 cmp::min(surface.get_height() as i32, rect.h));
+        if surface.blit(Some(src_rect), dest, Some(rect)) {
+            Ok(())
+        } else {
+            Err(get_error())
+        }
+    }
+
+    pub fn render_str_mode<C: ToColor>(&self, text: &str, mode: &RenderMode, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in the given mode.
+        match *mode {
+            RenderMode::Solid => self.render_str_solid(text, fg),
+            RenderMode::Shaded(ref bg) => self.render_str_shaded(text, fg, bg),
+            RenderMode::Blended => self.render_str_blended(text, fg)
+        }
+    }
+
+    pub fn prewarm<C: ToColor>(&self, chars: &[char], mode: RenderMode, fg: C) -> SdlResult<()> {
+        //! Draw and discard each of chars in the given mode, so that their
+        //! first real render does not pay for loading the glyphs. SDL2_ttf
+        //! caches few glyphs per font, so later ones may evict earlier ones.
+        let mut text = String::with_capacity(4);
+        for &ch in chars.iter() {
+            text.clear();
+            text.push(ch);
+            try!(self.render_str_mode(text.as_slice(), &mode, &fg));
+        }
+        Ok(())
+    }
+
+    pub fn render_fmt_blended<C: ToColor>(&self, args: &fmt::Arguments, fg: C) -> SdlResult<Surface> {
+        //! Draw formatted UTF8 text in blended mode, as render_str_blended
+        //! does, so the alpha of fg scales that of the glyphs too. Output
+        //! that fits in a small stack buffer is rendered without
+        //! allocating a String.
+        let mut buf = [0u8, ..FMT_BUFFER_SIZE];
+        let written = {
+            let mut writer = BufWriter::new(buf.as_mut_slice());
+            match writer.write_fmt(args) {
+                Ok(()) => writer.tell().ok(),
+                Err(_) => None
+            }
+        };
+        match written {
+            Some(0) => self.render_empty(),
+            // formatting only ever writes whole strs, so this is UTF8
+            Some(len) => self.render_str_blended(str::from_utf8(buf.slice_to(len as uint)).unwrap(), fg),
+            None => self.render_str_blended(fmt::format(args).as_slice(), fg)
+        }
+    }
+
+    pub fn build_atlas<C: ToColor>(&self, chars: &[char], fg: C) -> SdlResult<(Surface, HashMap<char, Rect>)> {
+        //! Draw each of chars in blended mode and pack the glyphs row by
+        //! row into a single surface. Also returns where each glyph was
+        //! placed in it.
+        let mut glyphs = Vec::with_capacity(chars.len());
+        let mut area = 0;
+        let mut widest = 0;
+        for &ch in chars.iter() {
+            if glyphs.iter().any(|&(c, _)| c == ch) {
+                continue;
             }
+            let glyph = try!(self.render_char_blended(ch, &fg));
+            area += glyph.get_width() * glyph.get_height();
+            widest = cmp::max(widest, glyph.get_width());
+            glyphs.push((ch, glyph));
+        }
+
+        // aim for a roughly square atlas
+        let width = cmp::max(widest, (area as f64).sqrt().ceil() as int);
+        let mut positions = Vec::with_capacity(glyphs.len());
+        let (mut x, mut y, mut row_height) = (0, 0, 0);
+        for &(_, ref glyph) in glyphs.iter() {
+            let (w, h) = (glyph.get_width(), glyph.get_height());
+            if x + w > width {
+                x = 0;
+                y += row_height;
+                row_height = 0;
+            }
+            positions.push((x, y));
+            x += w;
+            row_height = cmp::max(row_height, h);
+        }
+
+        let mut atlas = try!(new_argb_surface(width, y + row_height));
+        let mut rects = HashMap::new();
+        for ((ch, mut glyph), (x, y)) in glyphs.into_iter().zip(positions.into_iter()) {
+            try!(copy_at(&mut glyph, &mut atlas, x, y));
+            rects.insert(ch, Rect::new(x as i32, y as i32,
+                                       glyph.get_width() as i32, glyph.get_height() as i32));
+        }
+        Ok((atlas, rects))
+    }
+
+    pub fn render_lines_blended<C: ToColor>(&self, lines: &[(&str, C)], spacing: int) -> SdlResult<Surface> {
+        //! Draw lines of UTF8 text in blended mode, each in its own color,
+        //! stacked top to bottom in a single surface as wide as the widest
+        //! line. Lines are line_skip() plus spacing pixels apart.
+        let mut rendered = Vec::with_capacity(lines.len());
+        let mut width = 0;
+        for &(text, ref fg) in lines.iter() {
+            if text.is_empty() {
+                // SDL refuses to render zero width text; leave a gap instead
+                rendered.push(None);
+                continue;
+            }
+            let surface = try!(self.render_str_blended(text, fg));
+            width = cmp::max(width, surface.get_width());
+            rendered.push(Some(surface));
+        }
+
+        let advance = cmp::max(self.line_skip() + spacing, 0);
+        let height = match lines.len() {
+            0 => 0,
+            n => (n as int - 1) * advance + self.height()
+        };
+        let mut target = try!(new_argb_surface(width, height));
+        for (i, line) in rendered.into_iter().enumerate() {
+            match line {
+                Some(mut line) => try!(copy_at(&mut line, &mut target, 0, i as int * advance)),
+                None => {}
+            }
+        }
+        Ok(target)
+    }
+
+    pub fn render_str_blended_tracked<C: ToColor>(&self, text: &str, fg: C, tracking: int) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode with tracking extra pixels
+        //! between glyphs; negative tracking tightens it. Each glyph is
+        //! drawn on its own, so where they overlap they are merged rather
+        //! than drawn over each other. Glyphs never move back past the one
+        //! before them, and otherwise advance as in Font::layout; combining
+        //! marks are drawn along with the char before them.
+        let mut pen = Pen::new(self, tracking);
+        let clusters = split_clusters(text);
+        let mut glyphs = Vec::with_capacity(clusters.len());
+        let mut width = 0;
+        for &cluster in clusters.iter() {
+            let glyph = try!(self.render_str_blended(cluster, &fg));
+            let mut chars = cluster.chars();
+            let x = cmp::max(pen.place(self, chars.next().unwrap()), 0);
+            for ch in chars {
+                pen.place(self, ch);
+            }
+            width = cmp::max(width, x + glyph.get_width());
+            glyphs.push((x, glyph));
+        }
+
+        let mut target = try!(new_argb_surface(width, self.height()));
+        for &(x, ref glyph) in glyphs.iter() {
+            merge_at(glyph, &mut target, x, 0);
+        }
+        Ok(target)
+    }
+
+    pub fn render_str_blended_shadow<C: ToColor, D: ToColor>(&self, text: &str, fg: C, shadow: D,
+                                                           offset: (int, int)) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode over a shadow of it, offset by
+        //! (dx, dy) pixels. The surface grows to fit the shadow, to the
+        //! top or left for negative offsets.
+        let (dx, dy) = offset;
+        let shade = try!(self.render_str_blended(text, shadow));
+        let body = try!(self.render_str_blended(text, fg));
+        let mut target = try!(new_argb_surface(body.get_width() + dx.abs(),
+                                               body.get_height() + dy.abs()));
+        over_at(&shade, &mut target, cmp::max(dx, 0), cmp::max(dy, 0));
+        over_at(&body, &mut target, cmp::max(-dx, 0), cmp::max(-dy, 0));
+        Ok(target)
+    }
+
+    pub fn render_str_vertical_blended<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode as a single column, top to
+        //! bottom, each char centered horizontally and height() pixels
+        //! below the last. SDL2_ttf 2.0 has no vertical layout, so glyphs
+        //! keep their upright forms and there is no kerning between them.
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut width = 0;
+        let mut buf = String::with_capacity(4);
+        for ch in text.chars() {
+            buf.clear();
+            buf.push(ch);
+            let glyph = try!(self.render_str_blended(buf.as_slice(), &fg));
+            width = cmp::max(width, glyph.get_width());
+            glyphs.push(glyph);
+        }
+
+        let advance = self.height();
+        let mut target = try!(new_argb_surface(width, glyphs.len() as int * advance));
+        for (i, mut glyph) in glyphs.into_iter().enumerate() {
+            let x = (width - glyph.get_width()) / 2;
+            try!(copy_at(&mut glyph, &mut target, x, i as int * advance));
         }
+        Ok(target)
+    }
+
+    pub fn render_str_outlined<C: ToColor, D: ToColor>(&mut self, text: &str, fill: C, outline: D,
+                               outline_width: int) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode, surrounded by an outline of
+        //! outline_width pixels. The font's outline setting is restored
+        //! afterwards.
+        let old_outline = self.get_outline();
+        self.set_outline(outline_width);
+        let border = self.render_str_blended(text, outline);
+        self.set_outline(0);
+        let body = self.render_str_blended(text, fill);
+        self.set_outline(old_outline);
+
+        let mut border = try!(border);
+        let body = try!(body);
+        let x = (border.get_width() - body.get_width()) / 2;
+        let y = (border.get_height() - body.get_height()) / 2;
+        try!(blit_at(&body, &mut border, x, y));
+        Ok(border)
+    }
+
+    pub fn render_str_aliased<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in solid mode, without antialiasing, into an
+        //! ARGB8888 surface whose background is fully transparent. The
+        //! background is found by the solid surface's palette index 0
+        //! rather than a colorkey, so no foreground color collides with it.
+        let fg = color_to_c_color(fg.to_color());
+        let solid = try!(self.render_str_solid(text, pixels::Color::RGBA(fg.r, fg.g, fg.b, 255)));
+        let (w, h) = (solid.get_width() as uint, solid.get_height() as uint);
+        let pitch = solid.get_pitch() as uint;
+        let mut ink = Vec::with_capacity(w * h);
+        solid.with_lock(|pixels| {
+            for y in range(0, h) {
+                for x in range(0, w) {
+                    ink.push(pixels[y * pitch + x] != 0);
+                }
+            }
+        });
+
+        let target = try!(new_argb_surface(w as int, h as int));
+        let pitch = target.get_pitch() as uint;
+        target.with_lock(|pixels| {
+            for (i, &on) in ink.iter().enumerate() {
+                if on {
+                    let p = (i / w) * pitch + (i % w) * 4;
+                    pixels[p + RED_BYTE] = fg.r;
+                    pixels[p + GREEN_BYTE] = fg.g;
+                    pixels[p + BLUE_BYTE] = fg.b;
+                    pixels[p + ALPHA_BYTE] = fg.a;
+                }
+            }
+        });
+        Ok(target)
     }
 }
 
 
-/// Loader trait for RWops
-pub trait LoaderRWops {
-    /// Load src for use as a font.
-    fn load_font(&self, ptsize: int) -> SdlResult<Font>;
-    /// Load src for use as a font.
-    fn load_font_index(&self, ptsize: int, index: int) -> SdlResult<Font>;
+/// Builds the options of a font file to open, picking the loading
+/// function that takes them, e.g.
+/// `FontLoader::new(path).ptsize(24).index(1).dpi(144, 144).open()`.
+#[deriving(Clone)]
+pub struct FontLoader {
+    // an invalid path is only reported on opening, to keep the builder chain
+    path: SdlResult<Path>,
+    ptsize: Option<int>,
+    index: Option<int>,
+    dpi: Option<(u32, u32)>
 }
 
-impl LoaderRWops for RWops {
-    fn load_font(&self, ptsize: int) -> SdlResult<Font> {
-        let raw = unsafe {
-            ffi::TTF_OpenFontRW(self.raw(), 0, ptsize as c_int)
+impl FontLoader {
+    pub fn new<P: BytesContainer>(filename: P) -> FontLoader {
+        //! Start building the options of filename. The point size must be
+        //! set before opening; the face index defaults to 0, and the DPI
+        //! to 72 x 72. A filename no path can be built from fails to open.
+        FontLoader { path: to_path(filename), ptsize: None, index: None, dpi: None }
+    }
+
+    pub fn ptsize(mut self, ptsize: int) -> FontLoader {
+        //! Set the point size to open the font at.
+        self.ptsize = Some(ptsize);
+        self
+    }
+
+    pub fn index(mut self, index: int) -> FontLoader {
+        //! Set the face of the file to open.
+        self.index = Some(index);
+        self
+    }
+
+    pub fn dpi(mut self, hdpi: u32, vdpi: u32) -> FontLoader {
+        //! Set the resolution of the display the font is for.
+        self.dpi = Some((hdpi, vdpi));
+        self
+    }
+
+    pub fn open(&self) -> SdlResult<Font<'static>> {
+        //! Load the font with the options set.
+        let ptsize = match self.ptsize {
+            Some(ptsize) => ptsize,
+            None => return Err("point size not set".to_string())
         };
-        if raw.is_null() {
-            Err(get_error())
-        } else {
-            Ok(Font::from_ll(raw, true))
+        let path = try!(self.path.clone());
+        match (self.index, self.dpi) {
+            (index, Some((hdpi, vdpi))) => {
+                Font::from_file_index_dpi(&path, ptsize, index.unwrap_or(0), hdpi, vdpi)
+            }
+            (Some(index), None) => Font::from_file_index(&path, ptsize, index),
+            (None, None) => Font::from_file(&path, ptsize)
         }
     }
-    fn load_font_index(&self, ptsize: int, index: int) -> SdlResult<Font> {
-        let raw = unsafe {
-            ffi::TTF_OpenFontIndexRW(self.raw(), 0, ptsize as c_int, index as c_long)
+}
+
+/// An ordered list of fonts, drawing each character with the first font
+/// that provides it. This lets a primary font fall back to others, e.g.
+/// for CJK or symbols, instead of drawing missing glyph boxes.
+pub struct FontSet<'a> {
+    fonts: Vec<Font<'a>>
+}
+
+impl<'a> FontSet<'a> {
+    pub fn new(fonts: Vec<Font<'a>>) -> FontSet<'a> {
+        //! Create a set trying fonts in the given order.
+        FontSet { fonts: fonts }
+    }
+
+    pub fn fonts(&self) -> &[Font<'a>] {
+        //! Get the fonts of the set, in order.
+        self.fonts.as_slice()
+    }
+
+    pub fn runs<'b>(&self, text: &'b str) -> Vec<(uint, &'b str)> {
+        //! Split UTF8 text into runs drawn by a single font each, given by
+        //! its index in the set. Characters no font provides are left to
+        //! the first font.
+        let mut runs = Vec::new();
+        let mut start = 0;
+        let mut current = None;
+        for (i, ch) in text.char_indices() {
+            let font = self.font_for(ch);
+            match current {
+                Some(prev) if prev != font => {
+                    runs.push((prev, text.slice(start, i)));
+                    start = i;
+                }
+                _ => {}
+            }
+            current = Some(font);
+        }
+        match current {
+            Some(font) => runs.push((font, text.slice_from(start))),
+            None => {}
+        }
+        runs
+    }
+
+    pub fn render_str_blended<C: ToColor>(&self, text: &str, fg: C) -> SdlResult<Surface> {
+        //! Draw UTF8 text in blended mode, each run with its own font, side
+        //! by side on a common baseline.
+        if self.fonts.is_empty() {
+            return Err("font set is empty".to_string());
+        }
+        let runs = self.runs(text);
+        let baseline = runs.iter().map(|&(i, _)| self.fonts[i].ascent()).max().unwrap_or(0);
+        let mut surfaces = Vec::with_capacity(runs.len());
+        let (mut width, mut height) = (0, 0);
+        for &(i, run) in runs.iter() {
+            let font = &self.fonts[i];
+            let surface = try!(font.render_str_blended(run, &fg));
+            let y = baseline - font.ascent();
+            width += surface.get_width();
+            height = cmp::max(height, y + surface.get_height());
+            surfaces.push((surface, y));
+        }
+
+        let mut target = try!(new_argb_surface(width, height));
+        let mut x = 0;
+        for (mut surface, y) in surfaces.into_iter() {
+            try!(copy_at(&mut surface, &mut target, x, y));
+            x += surface.get_width();
+        }
+        Ok(target)
+    }
+
+    fn font_for(&self, ch: char) -> uint {
+        self.fonts.iter().position(|font| font.index_of_char(ch).is_some()).unwrap_or(0)
+    }
+}
+
+/// A texture of rendered text, borrowing the renderer that created it so
+/// that it cannot outlive it.
+pub struct TextTexture<'a> {
+    texture: Texture,
+    _renderer: &'a Renderer
+}
+
+impl<'a> TextTexture<'a> {
+    pub fn texture(&self) -> &Texture {
+        //! Get the texture, e.g. to copy it onto the renderer.
+        &self.texture
+    }
+}
+
+#[deriving(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    text: String,
+    fg: (u8, u8, u8, u8),
+    // the mode, and the background color if shaded
+    mode: (u8, (u8, u8, u8, u8))
+}
+
+impl CacheKey {
+    fn new(text: &str, fg: Color, mode: &RenderMode) -> CacheKey {
+        fn rgba(color: Color) -> (u8, u8, u8, u8) {
+            let c = color_to_c_color(color);
+            (c.r, c.g, c.b, c.a)
+        }
+        let mode = match *mode {
+            RenderMode::Solid => (0, (0, 0, 0, 0)),
+            RenderMode::Shaded(ref bg) => (1, rgba(bg.clone())),
+            RenderMode::Blended => (2, (0, 0, 0, 0))
         };
-        if raw.is_null() {
-            Err(get_error())
-        } else {
-            Ok(Font::from_ll(raw, true))
+        CacheKey { text: text.to_string(), fg: rgba(fg), mode: mode }
+    }
+}
+
+struct CacheEntry {
+    texture: Texture,
+    last_used: u64
+}
+
+/// Memoizes the textures of strings rendered with one font for one
+/// renderer, so that labels drawn every frame are only rendered once.
+///
+/// Once more than capacity textures are held, the least recently used one
+/// is dropped. Textures are borrowed from the cache, which cannot outlive
+/// the renderer that created them.
+pub struct TextCache<'a> {
+    renderer: &'a Renderer,
+    font: &'a Font<'a>,
+    capacity: uint,
+    clock: u64,
+    entries: HashMap<CacheKey, CacheEntry>
+}
+
+impl<'a> TextCache<'a> {
+    pub fn new(renderer: &'a Renderer, font: &'a Font<'a>, capacity: uint) -> TextCache<'a> {
+        //! Create an empty cache holding at most capacity textures (at
+        //! least one).
+        TextCache {
+            renderer: renderer,
+            font: font,
+            capacity: cmp::max(capacity, 1),
+            clock: 0,
+            entries: HashMap::new()
+        }
+    }
+
+    pub fn get<C: ToColor>(&mut self, text: &str, mode: RenderMode, fg: C) -> SdlResult<&Texture> {
+        //! Get the texture of text rendered in the given mode and color,
+        //! rendering it only if it is not cached yet.
+        let fg = fg.to_color();
+        let key = CacheKey::new(text, fg.clone(), &mode);
+        if !self.entries.contains_key(&key) {
+            let surface = try!(self.font.render_str_mode(text, &mode, fg));
+            let texture = try!(self.renderer.create_texture_from_surface(&surface));
+            if self.entries.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+            self.entries.insert(key.clone(), CacheEntry { texture: texture, last_used: 0 });
+        }
+        self.clock += 1;
+        let now = self.clock;
+        let entry = self.entries.get_mut(&key).unwrap();
+        entry.last_used = now;
+        Ok(&entry.texture)
+    }
+
+    pub fn len(&self) -> uint {
+        //! Get the number of cached textures.
+        self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        //! Drop every cached texture.
+        self.entries.clear();
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self.entries.iter()
+            .min_by(|&(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        match oldest {
+            Some(key) => { self.entries.remove(&key); }
+            None => {}
+        }
+    }
+}
+
+/// Moves along a line of text as SDL2_ttf 2.0 does, which does not shape:
+/// by the advance of each glyph, combining marks included, plus kerning if
+/// enabled. Chars outside the Basic Multilingual Plane, which SDL2_ttf 2.0
+/// has no metrics for, move it by the width of a space. Shared by
+/// Font::layout and the tracked renderer so that they agree.
+struct Pen {
+    x: int,
+    prev: Option<uint>,
+    kerning: bool,
+    fallback: int,
+    tracking: int
+}
+
+impl Pen {
+    /// Starts at 0, adding tracking pixels to every advance.
+    fn new(font: &Font, tracking: int) -> Pen {
+        Pen {
+            x: 0,
+            prev: None,
+            kerning: font.get_kerning(),
+            fallback: font.advance_of_char(' ').unwrap_or(font.height() / 2),
+            tracking: tracking
+        }
+    }
+
+    /// Moves past ch, returning where it is drawn.
+    fn place(&mut self, font: &Font, ch: char) -> int {
+        let index = font.index_of_char(ch);
+        match (self.prev, index) {
+            (Some(prev), Some(index)) if self.kerning => self.x += font.kerning_between(prev, index),
+            _ => {}
+        }
+        let x = self.x;
+        // glyphs the font lacks still have the metrics of its missing glyph
+        let advance = font.metrics_of_char(ch).map_or(self.fallback, |metrics| metrics.advance);
+        self.x += cmp::max(advance + self.tracking, 0);
+        self.prev = index;
+        x
+    }
+}
+
+/// The x offsets of every char boundary of a line of text, measured once so
+/// that a text editor can place its caret without measuring prefixes again.
+///
+/// Offsets are those Font::cursor_x gives, each prefix measured as
+/// SDL2_ttf measures it; as SDL2_ttf 2.0 does not shape, a combining mark
+/// moves the caret by its own advance like any other char. Build a new one
+/// whenever the line changes.
+#[deriving(Clone, PartialEq, Show)]
+pub struct LineMetrics {
+    // offsets[i] is the width of the first i chars
+    offsets: Vec<int>
+}
+
+impl LineMetrics {
+    pub fn new(font: &Font, text: &str) -> SdlResult<LineMetrics> {
+        //! Measure a line of UTF8 text.
+        try!(check_inited());
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        offsets.push(0);
+        for (i, _) in text.char_indices().skip(1) {
+            let (w, _) = try!(font.size_of_str(text.slice_to(i)));
+            offsets.push(w);
+        }
+        if !text.is_empty() {
+            let (w, _) = try!(font.size_of_str(text));
+            offsets.push(w);
+        }
+        Ok(LineMetrics { offsets: offsets })
+    }
+
+    pub fn x_at(&self, char_index: uint) -> int {
+        //! Get the x offset of a caret after the first char_index chars.
+        //! Indices past the end give the full width.
+        let last = self.offsets.len() - 1;
+        self.offsets[cmp::min(char_index, last)]
+    }
+
+    pub fn width(&self) -> int {
+        //! Get the width of the whole line.
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    pub fn len(&self) -> uint {
+        //! Get the number of chars of the line.
+        self.offsets.len() - 1
+    }
+}
+
+/// Renders text into a scratch surface that is kept between calls and only
+/// reallocated when a text does not fit, for HUD text that changes every
+/// frame.
+///
+/// This does not make rendering allocation free: SDL2_ttf 2.0 has no call
+/// that renders into a caller's surface, so each render still allocates a
+/// temporary surface inside SDL and copies it into the scratch one, which
+/// costs more than render_str_blended alone. What is kept stable is the
+/// surface handed back, e.g. for code that holds on to its pixels.
+pub struct TextRenderer {
+    scratch: Option<Surface>
+}
+
+impl TextRenderer {
+    pub fn new() -> TextRenderer {
+        //! Create a renderer; the scratch surface is allocated on first use.
+        TextRenderer { scratch: None }
+    }
+
+    pub fn render_into<C: ToColor>(&mut self, font: &Font, text: &str, fg: C) -> SdlResult<(&Surface, Rect)> {
+        //! Draw UTF8 text in blended mode into the scratch surface. Returns
+        //! the surface and the rect of it holding the text, which is all
+        //! that should be blitted. The borrow ends before the next call,
+        //! which overwrites the surface.
+        let mut text = try!(font.render_str_blended(text, fg));
+        let (w, h) = (text.get_width(), text.get_height());
+        let (cur_w, cur_h) = match self.scratch {
+            Some(ref scratch) => (scratch.get_width(), scratch.get_height()),
+            None => (0, 0)
+        };
+        if self.scratch.is_none() || w > cur_w || h > cur_h {
+            self.scratch = Some(try!(new_argb_surface(cmp::max(w, cur_w), cmp::max(h, cur_h))));
+        }
+        let scratch = self.scratch.as_mut().unwrap();
+        try!(copy_at(&mut text, scratch, 0, 0));
+        Ok((&*scratch, Rect::new(0, 0, w as i32, h as i32)))
+    }
+}
+
+// Face attributes don't depend on the size, so any valid one will do.
+const FACE_QUERY_PTSIZE: int = 12;
+
+pub fn count_faces<P: BytesContainer>(filename: P) -> SdlResult<int> {
+    //! Get the number of faces in a font file, without keeping it open.
+    let font = try!(Font::from_file(filename, FACE_QUERY_PTSIZE));
+    Ok(font.faces())
+}
+
+/// Attributes of one face of a font file.
+#[deriving(PartialEq, Clone, Show)]
+pub struct FaceInfo {
+    pub index: int,
+    pub family_name: Option<String>,
+    pub style_name: Option<String>,
+    pub fixed_width: bool
+}
+
+impl FaceInfo {
+    pub fn from_file<P: BytesContainer>(filename: P, index: int) -> SdlResult<FaceInfo> {
+        //! Read the attributes of face index of a font file.
+        let font = try!(Font::from_file_index(filename, FACE_QUERY_PTSIZE, index));
+        Ok(FaceInfo::from_font(&font, index))
+    }
+
+    pub fn all_from_file<P: BytesContainer>(filename: P) -> SdlResult<Vec<FaceInfo>> {
+        //! Read the attributes of every face of a font file.
+        let path = try!(to_path(filename));
+        let first = try!(Font::from_file_index(&path, FACE_QUERY_PTSIZE, 0));
+        let mut faces = vec![FaceInfo::from_font(&first, 0)];
+        for index in range(1, first.faces() as int) {
+            faces.push(try!(FaceInfo::from_file(&path, index)));
+        }
+        Ok(faces)
+    }
+
+    pub fn index_of_style<P: BytesContainer>(filename: P, style_name: &str) -> SdlResult<Option<int>> {
+        //! Find the face of a font file with the given style name, e.g.
+        //! "Bold" in a collection. As SDL2_ttf cannot select the named
+        //! instances of variable fonts, this is the way to pick a weight.
+        let faces = try!(FaceInfo::all_from_file(filename));
+        Ok(faces.iter()
+                .find(|face| face.style_name.as_ref().map_or(false, |name| name.as_slice() == style_name))
+                .map(|face| face.index))
+    }
+
+    fn from_font(font: &Font, index: int) -> FaceInfo {
+        FaceInfo {
+            index: index,
+            family_name: font.face_family_name(),
+            style_name: font.face_style_name(),
+            fixed_width: font.face_is_fixed_width()
+        }
+    }
+}
+
+/// Loader trait for RWops
+///
+/// load_font and load_font_index leave src to the caller: the loaded font
+/// reads from src lazily, so it borrows src for its whole lifetime.
+/// Several fonts, e.g. the faces of a collection, may be loaded from the
+/// same src. load_font_with instead gives src up to the font, which closes
+/// it. Loading advances the position of src.
+pub trait LoaderRWops<'a> {
+    /// Load src for use as a font.
+    fn load_font(&'a self, ptsize: int) -> SdlResult<Font<'a>>;
+    /// Load src for use as a font.
+    fn load_font_index(&'a self, ptsize: int, index: int) -> SdlResult<Font<'a>>;
+    /// Load src for use as a font, giving it up to the font, which closes
+    /// it when dropped; src is closed right away if loading fails. The
+    /// font borrows nothing, for streams that are not used again.
+    fn load_font_with(self, ptsize: int, index: int) -> SdlResult<Font<'static>>;
+}
+
+impl<'a> LoaderRWops<'a> for RWops {
+    fn load_font(&'a self, ptsize: int) -> SdlResult<Font<'a>> {
+        self.load_font_index(ptsize, 0)
+    }
+    fn load_font_index(&'a self, ptsize: int, index: int) -> SdlResult<Font<'a>> {
+        load_rw(self, ptsize, index)
+    }
+    fn load_font_with(self, ptsize: int, index: int) -> SdlResult<Font<'static>> {
+        let mut font = try!(load_rw(&self, ptsize, index));
+        font.stream = Some(self);
+        Ok(font)
+    }
+}
+
+/// Opens a font reading from src, without letting SDL close src.
+fn load_rw<'a>(src: &RWops, ptsize: int, index: int) -> SdlResult<Font<'a>> {
+    try!(check_inited());
+    let c_ptsize: c_int = try!(to_c(ptsize, "point size"));
+    let c_index: c_long = try!(to_c(index, "face index"));
+    let raw = unsafe {
+        ffi::TTF_OpenFontIndexRW(src.raw(), 0, c_ptsize, c_index)
+    };
+    if raw.is_null() {
+        Err(get_error())
+    } else {
+        Ok(Font::from_ll(raw, true, FontKey::synthetic("rwops", ptsize, index), FontSource::RWops))
+    }
+}
+
+
+pub fn pixel_format_for(mode: &RenderMode) -> pixels::PixelFormatFlag {
+    //! Get the pixel format of the surfaces rendered in a mode: 8-bit
+    //! palettized for solid and shaded, ARGB8888 for blended. Rendering
+    //! empty text gives an ARGB8888 surface in every mode.
+    match *mode {
+        RenderMode::Solid | RenderMode::Shaded(_) => pixels::PixelFormatFlag::Index8,
+        RenderMode::Blended => pixels::PixelFormatFlag::ARGB8888
+    }
+}
+
+pub fn into_rgba(surface: Surface) -> SdlResult<Surface> {
+    //! Convert a surface rendered in any mode to the 32-bit ARGB8888 format
+    //! of blended rendering, so that uploading code can assume that one
+    //! format. Solid and shaded surfaces are expanded from 8 bits a pixel,
+    //! which costs a copy at four times their size.
+    to_argb(&surface)
+}
+
+pub fn recolor_solid<C: ToColor>(surface: &mut Surface, fg: C) -> SdlResult<()> {
+    //! Change the text color of a surface rendered in solid mode, without
+    //! rendering it again, by rewriting the foreground entry of its
+    //! palette. Fails for surfaces of any other format.
+    let color = color_to_c_color(fg.to_color());
+    unsafe {
+        let format = (*surface.raw()).format;
+        let palette = (*format).palette;
+        // solid surfaces, unlike shaded ones, key out the background at
+        // index 0 and draw the text with index 1
+        let mut key = 0;
+        let keyed = ffi::SDL_GetColorKey(surface.raw(), &mut key) == 0;
+        if (*format).format != ffi::SDL_PIXELFORMAT_INDEX8 || palette.is_null() || !keyed || key != 0 {
+            return Err("surface was not rendered in solid mode".to_string());
+        }
+        if ffi::SDL_SetPaletteColors(palette, &color, 1, 1) != 0 {
+            return Err(get_error());
         }
     }
+    Ok(())
+}
+
+pub fn rgba_pixels(surface: &Surface) -> SdlResult<(Vec<u8>, int, int)> {
+    //! Copy the pixels of a surface, whatever its format, out as RGBA8
+    //! bytes: red, green, blue, alpha, row by row without padding. Also
+    //! returns the width and height.
+    let argb = try!(to_argb(surface));
+    let (w, h) = (argb.get_width() as uint, argb.get_height() as uint);
+    let pitch = argb.get_pitch() as uint;
+    let mut rgba = Vec::with_capacity(w * h * 4);
+    argb.with_lock(|pixels| {
+        for y in range(0, h) {
+            for x in range(0, w) {
+                let p = y * pitch + x * 4;
+                rgba.push(pixels[p + RED_BYTE]);
+                rgba.push(pixels[p + GREEN_BYTE]);
+                rgba.push(pixels[p + BLUE_BYTE]);
+                rgba.push(pixels[p + ALPHA_BYTE]);
+            }
+        }
+    });
+    Ok((rgba, w as int, h as int))
 }
@@ -0,0 +1,390 @@
+#![feature(macro_rules)]
+
+extern crate sdl2;
+extern crate sdl2_ttf;
+
+use std::iter::{range_inclusive, range_step};
+use sdl2::pixels::Color;
+use sdl2_ttf::{Font, Hinting, Latin1, LineMetrics, RenderMode};
+
+// These tests share the FreeType library under SDL2_ttf, which is not
+// thread safe, and some init or quit it. Run them one at a time, with
+// RUST_TEST_TASKS=1 cargo test.
+
+static FONT_PATH: &'static str = "tests/fonts/DejaVuSansMono.ttf";
+
+// init the library and load the bundled font, or skip the test
+macro_rules! font_or_skip(
+    ($ptsize:expr) => (
+        if !sdl2_ttf::init() {
+            println!("skipping: SDL2_ttf could not be initialized");
+            return;
+        } else {
+            Font::from_file(FONT_PATH, $ptsize).unwrap()
+        }
+    )
+)
+
+fn red() -> Color {
+    Color::RGBA(255, 0, 0, 255)
+}
+
+#[test]
+fn test_load_font() {
+    let font = font_or_skip!(24);
+    assert!(font.height() > 0);
+    assert!(font.faces() >= 1);
+    assert!(font.face_is_fixed_width());
+}
+
+#[test]
+fn test_load_missing_file() {
+    if !sdl2_ttf::init() {
+        return;
+    }
+    assert!(Font::from_file("tests/fonts/missing.ttf", 24).is_err());
+}
+
+#[test]
+fn test_negative_face_index() {
+    if !sdl2_ttf::init() {
+        return;
+    }
+    let error = Font::from_file_index(FONT_PATH, 24, -1).err().unwrap();
+    assert!(error.as_slice().contains("out of range"));
+}
+
+#[test]
+fn test_nul_in_path() {
+    if !sdl2_ttf::init() {
+        return;
+    }
+    assert!(Font::from_file("tests/fonts/\0.ttf", 24).is_err());
+    assert!(Font::from_file_index("tests/fonts/\0.ttf", 24, 1).is_err());
+    assert!(sdl2_ttf::FontLoader::new("tests/fonts/\0.ttf").ptsize(24).open().is_err());
+    assert!(sdl2_ttf::FaceInfo::all_from_file("tests/fonts/\0.ttf").is_err());
+}
+
+#[test]
+fn test_render_modes() {
+    let font = font_or_skip!(24);
+    let bg = Color::RGB(0, 0, 0);
+    let (w, h) = font.size_of_str("Hello").unwrap();
+    let surfaces = vec![
+        font.render_str_solid("Hello", red()).unwrap(),
+        font.render_str_shaded("Hello", red(), bg).unwrap(),
+        font.render_str_blended("Hello", red()).unwrap(),
+        font.render_bytes_solid(Latin1(b"Hello"), red()).unwrap(),
+        font.render_bytes_shaded(Latin1(b"Hello"), red(), bg).unwrap(),
+        font.render_bytes_blended(Latin1(b"Hello"), red()).unwrap(),
+    ];
+    for surface in surfaces.iter() {
+        assert_eq!(surface.get_width(), w);
+        assert_eq!(surface.get_height(), h);
+    }
+}
+
+#[test]
+fn test_render_char_modes() {
+    let font = font_or_skip!(24);
+    let bg = Color::RGB(0, 0, 0);
+    let surfaces = vec![
+        font.render_char_solid('A', red()).unwrap(),
+        font.render_char_shaded('A', red(), bg).unwrap(),
+        font.render_char_blended('A', red()).unwrap(),
+    ];
+    for surface in surfaces.iter() {
+        assert!(surface.get_width() > 0);
+        assert!(surface.get_height() > 0);
+    }
+}
+
+#[test]
+fn test_glyph_metrics() {
+    let font = font_or_skip!(24);
+    let metrics = font.metrics_of_char('A').unwrap();
+    assert!(metrics.width() > 0);
+    assert!(metrics.height() > 0);
+    assert!(metrics.advance > 0);
+    assert!(font.index_of_char('A').is_some());
+}
+
+#[test]
+fn test_outline() {
+    let mut font = font_or_skip!(24);
+    let plain = font.render_str_blended("Hello", red()).unwrap();
+
+    font.set_outline(-5);
+    assert_eq!(font.get_outline(), 0);
+    let clamped = font.render_str_blended("Hello", red()).unwrap();
+    assert_eq!(clamped.get_width(), plain.get_width());
+
+    font.set_outline(2);
+    let outlined = font.render_str_blended("Hello", red()).unwrap();
+    assert!(outlined.get_width() > plain.get_width());
+}
+
+#[test]
+fn test_outline_round_trip() {
+    let mut font = font_or_skip!(24);
+    for outline in range_inclusive(0, 8) {
+        font.set_outline(outline);
+        assert_eq!(font.get_outline(), outline);
+    }
+    font.set_outline(0);
+    assert_eq!(font.get_outline(), 0);
+}
+
+#[test]
+fn test_outline_padding() {
+    let mut font = font_or_skip!(24);
+    assert_eq!(font.outline_padding(), 0);
+    let plain = font.render_str_blended("Hello", red()).unwrap();
+
+    font.set_outline(3);
+    assert_eq!(font.outline_padding(), 3);
+    let outlined = font.render_str_blended("Hello", red()).unwrap();
+    assert_eq!(outlined.get_width(), plain.get_width() + 2 * 3);
+    assert_eq!(outlined.get_height(), plain.get_height() + 2 * 3);
+}
+
+#[test]
+fn test_render_str_outlined_restores_outline() {
+    let mut font = font_or_skip!(24);
+    font.set_outline(1);
+    font.render_str_outlined("Hello", red(), Color::RGB(0, 0, 0), 3).unwrap();
+    assert_eq!(font.get_outline(), 1);
+}
+
+#[test]
+fn test_font_outlives_context() {
+    // start from no user init, so that the font is all that can keep the
+    // library initialized once quit() is called
+    sdl2_ttf::quit();
+    if !sdl2_ttf::init() {
+        return;
+    }
+    let font = Font::from_file(FONT_PATH, 24).unwrap();
+    sdl2_ttf::quit();
+    assert!(sdl2_ttf::was_inited());
+    font.render_str_blended("Hello", red()).unwrap();
+    drop(font);
+    // dropping the font released the last reference
+    assert!(!sdl2_ttf::was_inited());
+}
+
+#[test]
+fn test_metrics_follow_size() {
+    let small = font_or_skip!(12);
+    let large = Font::from_file(FONT_PATH, 48).unwrap();
+    assert!(large.height() > small.height());
+    assert!(large.ascent() > small.ascent());
+    assert!(large.descent() < small.descent());
+    assert!(large.line_skip() > small.line_skip());
+}
+
+#[test]
+fn test_non_bmp_char() {
+    let font = font_or_skip!(24);
+    // U+1D54F MATHEMATICAL DOUBLE-STRUCK CAPITAL X would truncate to U+D54F
+    let ch = '\U0001D54F';
+    assert!(font.index_of_char(ch).is_none());
+    assert!(font.metrics_of_char(ch).is_none());
+    assert!(font.render_char_solid(ch, red()).is_err());
+    assert!(font.render_char_blended(ch, red()).is_err());
+}
+
+#[test]
+fn test_with_style_restores_style() {
+    let mut font = font_or_skip!(24);
+    font.set_style(sdl2_ttf::StyleItalic);
+    let bold = font.with_style(sdl2_ttf::StyleBold, |f| f.is_bold() && !f.is_italic());
+    assert!(bold);
+    assert!(font.is_italic());
+    assert!(!font.is_bold());
+}
+
+#[test]
+fn test_from_bytes() {
+    if !sdl2_ttf::init() {
+        return;
+    }
+    let data = include_bin!("fonts/DejaVuSansMono.ttf").to_vec();
+    let font = Font::from_bytes(data, 24).unwrap();
+    let surface = font.render_str_blended("Hello", red()).unwrap();
+    assert!(surface.get_width() > 0);
+    drop(font);
+    assert!(Font::from_bytes(vec![0u8, 1, 2, 3], 24).is_err());
+}
+
+#[test]
+fn test_font_style_bits_round_trip() {
+    use sdl2_ttf::{FontStyle, StyleBold, StyleNormal, StyleUnderline};
+
+    // FontStyle is not Show, so compare with assert! rather than assert_eq!
+    assert!(StyleNormal == FontStyle::empty());
+    let style = StyleBold | StyleUnderline;
+    assert!(FontStyle::from_bits(style.bits()) == Some(style));
+    assert!(FontStyle::from_bits(FontStyle::all().bits()) == Some(FontStyle::all()));
+    assert!(FontStyle::from_bits(0x100).is_none());
+}
+
+#[test]
+fn test_blended_fg_alpha_scales_surface_alpha() {
+    let font = font_or_skip!(24);
+    let opaque = font.render_str_blended("Hello", red()).unwrap();
+    let faded = font.render_str_blended("Hello", Color::RGBA(255, 0, 0, 128)).unwrap();
+    let (opaque, _, _) = sdl2_ttf::rgba_pixels(&opaque).unwrap();
+    let (faded, _, _) = sdl2_ttf::rgba_pixels(&faded).unwrap();
+    assert_eq!(opaque.len(), faded.len());
+    // every fourth byte, from the fourth, is an alpha value
+    for i in range_step(3, opaque.len(), 4) {
+        let expected = (opaque[i] as int * 128 + 127) / 255;
+        assert!((faded[i] as int - expected).abs() <= 1);
+    }
+}
+
+#[test]
+fn test_render_empty_str() {
+    let font = font_or_skip!(24);
+    let surface = font.render_str_blended("", red()).unwrap();
+    assert_eq!(surface.get_width(), 0);
+    assert_eq!(surface.get_height(), font.height());
+}
+
+#[test]
+fn test_face_names() {
+    let font = font_or_skip!(24);
+    assert_eq!(font.face_family_name(), Some("DejaVu Sans Mono".to_string()));
+    assert_eq!(font.face_style_name(), Some("Book".to_string()));
+}
+
+#[test]
+fn test_layout_keeps_combining_marks_with_their_base() {
+    let font = font_or_skip!(24);
+    let layout = font.layout("ae\u0301x").unwrap();
+    let texts: Vec<&str> = layout.iter().map(|&(text, _)| text).collect();
+    assert_eq!(texts, vec!["a", "e\u0301", "x"]);
+    // the accented letter is placed and sized as size_of_str measures it
+    let (_, accented) = layout[1];
+    let (before, _) = font.size_of_str("a").unwrap();
+    let (w, h) = font.size_of_str("e\u0301").unwrap();
+    assert_eq!(accented.x as int, before);
+    assert_eq!((accented.w as int, accented.h as int), (w, h));
+    let (_, next) = layout[2];
+    let (before, _) = font.size_of_str("ae\u0301").unwrap();
+    assert_eq!(next.x as int, before);
+}
+
+#[test]
+fn test_line_metrics_match_cursor_x() {
+    let font = font_or_skip!(24);
+    let text = "ae\u0301x";
+    let metrics = LineMetrics::new(&font, text).unwrap();
+    assert_eq!(metrics.len(), 4);
+    for i in range_inclusive(0, 4) {
+        assert_eq!(metrics.x_at(i), font.cursor_x(text, i).unwrap());
+    }
+}
+
+#[test]
+fn test_failed_set_size_keeps_font() {
+    let mut font = font_or_skip!(24);
+    let height = font.height();
+    assert!(font.set_size(0).is_err());
+    assert_eq!(font.height(), height);
+    assert!(font.render_str_blended("Hello", red()).is_ok());
+
+    font.set_size(48).unwrap();
+    assert!(font.height() > height);
+}
+
+#[test]
+fn test_pixel_format_for() {
+    let font = font_or_skip!(24);
+    let modes = [RenderMode::Solid, RenderMode::Shaded(Color::RGB(0, 0, 0)), RenderMode::Blended];
+    for mode in modes.iter() {
+        let surface = font.render_str_mode("Hello", mode, red()).unwrap();
+        let format = unsafe { (*(*surface.raw()).format).format };
+        assert_eq!(format as u32, sdl2_ttf::pixel_format_for(mode) as u32);
+    }
+}
+
+#[test]
+fn test_hinting_round_trip() {
+    let mut font = font_or_skip!(24);
+    let mut hintings = vec![Hinting::HintingNormal, Hinting::HintingLight,
+                            Hinting::HintingMono, Hinting::HintingNone];
+    if sdl2_ttf::linked_version_at_least(2, 0, 18) {
+        hintings.push(Hinting::HintingLightSubpixel);
+    }
+    for &hinting in hintings.iter() {
+        font.set_hinting(hinting);
+        assert_eq!(font.get_hinting(), hinting);
+    }
+}
+
+#[test]
+fn test_font_style_iter_set() {
+    use sdl2_ttf::{FontStyle, StyleBold, StyleItalic, StyleNormal, StyleStrikeThrough, StyleUnderline};
+
+    assert_eq!(StyleNormal.iter_set().count(), 0);
+    assert!(FontStyle::from_styles(&[]) == StyleNormal);
+
+    let all = [StyleBold, StyleItalic, StyleUnderline, StyleStrikeThrough];
+    let styles: Vec<FontStyle> = FontStyle::all().iter_set().collect();
+    assert!(styles.as_slice() == all.as_slice());
+    assert!(FontStyle::from_styles(&all) == FontStyle::all());
+}
+
+#[test]
+fn test_recolor_solid() {
+    let font = font_or_skip!(24);
+    let mut solid = font.render_str_solid("Hello", red()).unwrap();
+    sdl2_ttf::recolor_solid(&mut solid, Color::RGB(0, 0, 255)).unwrap();
+    let text = unsafe { *(*(*(*solid.raw()).format).palette).colors.offset(1) };
+    assert_eq!((text.r, text.g, text.b), (0, 0, 255));
+
+    let mut shaded = font.render_str_shaded("Hello", red(), Color::RGB(0, 0, 0)).unwrap();
+    assert!(sdl2_ttf::recolor_solid(&mut shaded, Color::RGB(0, 0, 255)).is_err());
+}
+
+#[test]
+fn test_shadow_keeps_body_color_outside_shadow() {
+    let font = font_or_skip!(24);
+    let (w, _) = font.size_of_str("H").unwrap();
+    // offset the shadow by a full width, so no body pixel lies over it
+    let surface = font.render_str_blended_shadow("H", red(), Color::RGB(0, 0, 0), (w, 0)).unwrap();
+    let (pixels, width, height) = sdl2_ttf::rgba_pixels(&surface).unwrap();
+    let mut edges = 0u;
+    for y in range(0, height as uint) {
+        for x in range(0, w as uint) {
+            let p = (y * width as uint + x) * 4;
+            let alpha = pixels[p + 3];
+            if alpha > 0 && alpha < 255 {
+                edges += 1;
+                assert_eq!((pixels[p], pixels[p + 1], pixels[p + 2]), (255, 0, 0));
+            }
+        }
+    }
+    assert!(edges > 0);
+}
+
+#[test]
+fn test_fmt_blended_fg_alpha_scales_surface_alpha() {
+    let font = font_or_skip!(24);
+    let faded = Color::RGBA(255, 0, 0, 128);
+    let expected = font.render_str_blended("60 fps", faded).unwrap();
+    let formatted = format_args!(|args| font.render_fmt_blended(args, faded), "{} fps", 60i).unwrap();
+    let (expected, _, _) = sdl2_ttf::rgba_pixels(&expected).unwrap();
+    let (formatted, _, _) = sdl2_ttf::rgba_pixels(&formatted).unwrap();
+    assert!(expected == formatted);
+}
+
+#[test]
+fn test_render_empty_fmt() {
+    let font = font_or_skip!(24);
+    let surface = format_args!(|args| font.render_fmt_blended(args, red()), "{}", "").unwrap();
+    assert_eq!(surface.get_width(), 0);
+    assert_eq!(surface.get_height(), font.height());
+}